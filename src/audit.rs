@@ -0,0 +1,90 @@
+use std::fs::OpenOptions;
+use std::io::Write as _;
+use std::path::Path;
+
+use crate::db::{self, Db};
+use crate::time;
+
+pub const AUDIT_NAME: &str = "audit.log";
+
+/// describes the changes made between the previous and current db
+fn describe_changes(previous: Option<&Db>, current: &Db) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    let Some(previous) = previous else {
+        changes.push(format!("created db with {} files", current.files.len()));
+
+        return changes;
+    };
+
+    if db::entry_changed(current, previous) {
+        changes.push("modified !SELF".to_owned());
+    }
+
+    for (key, file) in &current.files {
+        match previous.files.get(key) {
+            Some(existing) => if db::entry_changed(file, existing) {
+                changes.push(format!("modified {key}"));
+            }
+            None => changes.push(format!("added {key}")),
+        }
+    }
+
+    for key in previous.files.keys() {
+        if !current.files.contains_key(key) {
+            changes.push(format!("removed {key}"));
+        }
+    }
+
+    for (name, coll) in &current.collections {
+        match previous.collections.get(name) {
+            Some(existing) => if coll != existing {
+                changes.push(format!("modified collection {name}"));
+            }
+            None => changes.push(format!("added collection {name}")),
+        }
+    }
+
+    for name in previous.collections.keys() {
+        if !current.collections.contains_key(name) {
+            changes.push(format!("removed collection {name}"));
+        }
+    }
+
+    changes
+}
+
+/// appends a line to the audit log in the given .fsm directory describing the
+/// command that was run and the entries that it changed
+///
+/// this is best effort, a failure is displayed as a warning and otherwise
+/// ignored
+pub fn record(fsm_dir: &Path, previous: Option<&Db>, current: &Db) {
+    let audit_path = fsm_dir.join(AUDIT_NAME);
+
+    let command: Vec<String> = std::env::args()
+        .skip(1)
+        .collect();
+    let mut changes = describe_changes(previous, current);
+
+    if changes.is_empty() {
+        changes.push("no changes".to_owned());
+    }
+
+    let line = format!(
+        "{} fsm {} | {}\n",
+        time::datetime_now().to_rfc3339(),
+        command.join(" "),
+        changes.join(", ")
+    );
+
+    let result = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(&audit_path)
+        .and_then(|mut file| file.write_all(line.as_bytes()));
+
+    if let Err(err) = result {
+        eprintln!("warning: failed writing audit log {}: {}", audit_path.display(), err);
+    }
+}
@@ -5,6 +5,12 @@ mod create;
 mod push;
 mod pop;
 mod delete;
+mod rename;
+mod combine;
+mod add_by_tag;
+mod export;
+mod import;
+mod auto;
 
 #[derive(Debug, Args)]
 pub struct CollectionArgs {
@@ -24,6 +30,18 @@ enum ManageCmd {
     Pop(pop::PopArgs),
     /// delete a given collection
     Delete(delete::DeleteArgs),
+    /// rename a given collection
+    Rename(rename::RenameArgs),
+    /// combine collections into a new collection
+    Combine(combine::CombineArgs),
+    /// add files matching tag filters to a given collection
+    AddByTag(add_by_tag::AddByTagArgs),
+    /// print the files of a given collection one per line
+    Export(export::ExportArgs),
+    /// add files listed in a file to a given collection
+    Import(import::ImportArgs),
+    /// create or update a collection for each value of a tag
+    Auto(auto::AutoArgs),
 }
 
 pub fn manage(args: CollectionArgs) -> anyhow::Result<()> {
@@ -33,5 +51,11 @@ pub fn manage(args: CollectionArgs) -> anyhow::Result<()> {
         ManageCmd::Push(push_args) => push::push_coll(push_args),
         ManageCmd::Pop(pop_args) => pop::pop_coll(pop_args),
         ManageCmd::Delete(delete_args) => delete::delete_coll(delete_args),
+        ManageCmd::Rename(rename_args) => rename::rename_coll(rename_args),
+        ManageCmd::Combine(combine_args) => combine::combine_coll(combine_args),
+        ManageCmd::AddByTag(add_args) => add_by_tag::add_by_tag(add_args),
+        ManageCmd::Export(export_args) => export::export_coll(export_args),
+        ManageCmd::Import(import_args) => import::import_coll(import_args),
+        ManageCmd::Auto(auto_args) => auto::auto_coll(auto_args),
     }
 }
@@ -0,0 +1,30 @@
+use clap::Args;
+
+use crate::filter;
+use crate::coll::push;
+
+#[derive(Debug, Args)]
+pub struct AddByTagArgs {
+    /// the name of the collection to add files to
+    name: String,
+
+    /// only adds files that exist
+    #[arg(long)]
+    if_exists: bool,
+
+    /// displays the files that would be added without saving them
+    #[arg(long)]
+    dry_run: bool,
+
+    #[command(flatten)]
+    filter: filter::FilterArgs,
+}
+
+/// adds the files matching the filter, same as coll push with only filters
+pub fn add_by_tag(args: AddByTagArgs) -> anyhow::Result<()> {
+    if !args.filter.is_set() {
+        return Err(anyhow::anyhow!("at least one filter must be given to add files by tag"));
+    }
+
+    push::push_coll(push::PushArgs::from_filter(args.name, args.if_exists, args.dry_run, args.filter))
+}
@@ -0,0 +1,118 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use clap::Args;
+
+use crate::db;
+use crate::tags;
+
+#[derive(Debug, Args)]
+pub struct AutoArgs {
+    /// the tag to create collections from
+    #[arg(long)]
+    by_tag: String,
+
+    /// the prefix of the collection names
+    ///
+    /// defaults to the tag followed by a colon, e.g. `project:` will create
+    /// `project:webapp` for files with a tag of `project:webapp`
+    #[arg(long)]
+    prefix: Option<String>,
+
+    /// removes files that no longer have the tag value from the collections
+    ///
+    /// this applies to all collections that start with the prefix.
+    /// collections that are empty afterwards are deleted
+    #[arg(long)]
+    prune: bool,
+}
+
+/// collects the values of a tag, list tags produce an entry for each value
+fn collect_values(value: &tags::TagValue, rtn: &mut Vec<String>) {
+    if let tags::TagValue::List(list) = value {
+        for item in list {
+            collect_values(item, rtn);
+        }
+    } else {
+        rtn.push(value.to_string());
+    }
+}
+
+pub fn auto_coll(args: AutoArgs) -> anyhow::Result<()> {
+    let mut context = db::Context::cwd_load()?;
+
+    let prefix = args.prefix.unwrap_or_else(|| format!("{}:", args.by_tag));
+    let mut groups: BTreeMap<String, BTreeSet<Box<str>>> = BTreeMap::new();
+
+    for (key, file) in &context.db.files {
+        let Some(Some(value)) = file.tags.get(&args.by_tag) else {
+            continue;
+        };
+
+        let mut values = Vec::new();
+
+        collect_values(value, &mut values);
+
+        for value in values {
+            groups.entry(format!("{}{}", prefix, value))
+                .or_default()
+                .insert(key.clone());
+        }
+    }
+
+    let mut created = 0usize;
+    let mut added = 0usize;
+    let mut removed = 0usize;
+    let mut deleted = 0usize;
+
+    if args.prune {
+        context.db.collections.retain(|name, coll| {
+            if !name.starts_with(&prefix) {
+                return true;
+            }
+
+            let matching = groups.get(name);
+            let before = coll.len();
+
+            coll.retain(|key| matching.is_some_and(|keys| keys.contains(key)));
+
+            removed += before - coll.len();
+
+            if coll.is_empty() {
+                log::info!("deleting empty collection {}", name);
+
+                deleted += 1;
+
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    for (name, keys) in groups {
+        let coll = context.db.collections.entry(name)
+            .or_insert_with(|| {
+                created += 1;
+
+                BTreeSet::new()
+            });
+
+        for key in keys {
+            if coll.insert(key) {
+                added += 1;
+            }
+        }
+    }
+
+    if created > 0 || added > 0 || removed > 0 || deleted > 0 {
+        context.save()?;
+    }
+
+    println!("created {created} collections, added {added} files");
+
+    if args.prune {
+        println!("removed {removed} files, deleted {deleted} collections");
+    }
+
+    Ok(())
+}
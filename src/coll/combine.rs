@@ -0,0 +1,75 @@
+use std::collections::BTreeSet;
+
+use clap::{Args, ArgGroup};
+
+use crate::db;
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("op").required(true)))]
+pub struct CombineArgs {
+    /// includes files that are in any of the collections
+    #[arg(long, group("op"))]
+    union: bool,
+
+    /// includes files that are in all of the collections
+    #[arg(long, group("op"))]
+    intersect: bool,
+
+    /// includes files in the first collection that are not in the others
+    #[arg(long, group("op"))]
+    diff: bool,
+
+    /// the name of the collection to save the result to
+    ///
+    /// if the collection already exists then it will be replaced. if not
+    /// specified then the result will be displayed without saving
+    #[arg(long)]
+    into: Option<String>,
+
+    /// the names of the collections to combine
+    #[arg(num_args(2..), required(true))]
+    names: Vec<String>,
+}
+
+pub fn combine_coll(args: CombineArgs) -> anyhow::Result<()> {
+    let mut context = db::Context::cwd_load()?;
+
+    let mut sources = Vec::with_capacity(args.names.len());
+
+    for name in &args.names {
+        let Some(coll) = context.db.collections.get(name) else {
+            return Err(anyhow::anyhow!("collection not found: {}", name));
+        };
+
+        sources.push(coll);
+    }
+
+    let mut iter = sources.into_iter();
+    let mut result: BTreeSet<Box<str>> = iter.next().unwrap().clone();
+
+    for coll in iter {
+        if args.union {
+            result.extend(coll.iter().cloned());
+        } else if args.intersect {
+            result.retain(|file| coll.contains(file));
+        } else {
+            result.retain(|file| !coll.contains(file));
+        }
+    }
+
+    if let Some(into) = args.into {
+        log::info!("saving {} files to collection {}", result.len(), into);
+
+        context.db.collections.insert(into, result);
+
+        context.save()?;
+    } else {
+        for file in &result {
+            println!("{}", file);
+        }
+
+        println!("{} files", result.len());
+    }
+
+    Ok(())
+}
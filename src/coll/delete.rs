@@ -1,6 +1,7 @@
 use clap::Args;
 
 use crate::db;
+use crate::error;
 
 #[derive(Debug, Args)]
 pub struct DeleteArgs {
@@ -16,8 +17,7 @@ pub fn delete_coll(args: DeleteArgs) -> anyhow::Result<()> {
     let mut context = db::Context::cwd_load()?;
 
     let Some(files) = context.db.collections.remove(&args.name) else {
-        println!("collection not found");
-        return Ok(());
+        return error::not_found("collection not found");
     };
 
     context.save()?;
@@ -0,0 +1,71 @@
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::Args;
+use anyhow::Context;
+
+use crate::db;
+use crate::path;
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// the name of the collection to export
+    name: String,
+
+    /// resolves each file to its absolute path on the file system
+    #[arg(long)]
+    absolute: bool,
+
+    /// writes the list to a file instead of stdout
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+fn write_files<W>(mut output: W, files: &[String]) -> anyhow::Result<()>
+where
+    W: Write
+{
+    for file in files {
+        writeln!(output, "{}", file).context("failed writing collection to output")?;
+    }
+
+    output.flush().context("failed writing collection to output")?;
+
+    Ok(())
+}
+
+pub fn export_coll(args: ExportArgs) -> anyhow::Result<()> {
+    let context = db::Context::load_readonly()?;
+
+    let Some(coll) = context.db.collections.get(&args.name) else {
+        return Err(anyhow::anyhow!("collection not found: {}", args.name));
+    };
+
+    let files: Vec<String> = coll.iter()
+        .map(|file| if args.absolute {
+            path::entry_to_path(context.root(), file)
+                .display()
+                .to_string()
+        } else {
+            file.to_string()
+        })
+        .collect();
+
+    if let Some(out) = &args.out {
+        let file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(out)
+            .with_context(|| format!("failed to open output file: {}", out.display()))?;
+
+        write_files(BufWriter::new(file), &files)?;
+
+        log::info!("exported {} files to {}", files.len(), out.display());
+    } else {
+        write_files(BufWriter::new(std::io::stdout().lock()), &files)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::logging;
+use crate::path;
+use crate::db;
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// the name of the collection to add files to
+    ///
+    /// the collection will be created if it does not exist
+    name: String,
+
+    /// the file containing newline separated paths
+    #[arg(long)]
+    from: PathBuf,
+}
+
+pub fn import_coll(args: ImportArgs) -> anyhow::Result<()> {
+    let mut context = db::Context::cwd_load()?;
+    let path_list = path::read_path_list(&args.from)?;
+    let files_iter = context.rel_to_db_list(&path_list);
+
+    let coll = context.db.collections.entry(args.name)
+        .or_default();
+
+    let mut added = 0usize;
+    let mut skipped = 0usize;
+
+    for path_result in files_iter {
+        let Some(rel_path) = logging::log_result(path_result) else {
+            skipped += 1;
+            continue;
+        };
+
+        let (_path, db_entry) = rel_path.into();
+
+        if coll.insert(db_entry) {
+            added += 1;
+        }
+    }
+
+    context.save()?;
+
+    println!("added {} files", added);
+
+    if skipped > 0 {
+        println!("skipped {} paths that could not be resolved", skipped);
+    }
+
+    Ok(())
+}
@@ -4,8 +4,11 @@ use std::path::PathBuf;
 use clap::Args;
 
 use crate::logging;
+use crate::path;
 use crate::db;
+use crate::error;
 use crate::fs;
+use crate::filter;
 
 #[derive(Debug, Args)]
 pub struct PopArgs {
@@ -16,40 +19,66 @@ pub struct PopArgs {
     #[arg(long)]
     no_exists:bool,
 
+    /// reads newline separated paths to pop from a file, or stdin with -
+    ///
+    /// the paths are popped after any files given as arguments
+    #[arg(long)]
+    from_file: Option<PathBuf>,
+
+    /// displays the files that would be removed without saving them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// pops all files in the collection that match the filter
+    ///
+    /// files without an entry in the db will not match
+    #[command(flatten)]
+    filter: filter::FilterArgs,
+
     /// the file(s) to pop
-    #[arg(
-        trailing_var_arg(true),
-        required_unless_present("no_exists")
-    )]
+    #[arg(trailing_var_arg(true))]
     files: Vec<PathBuf>,
 }
 
 pub fn pop_coll(args: PopArgs) -> anyhow::Result<()> {
+    if args.files.is_empty() && !args.no_exists && args.from_file.is_none() && !args.filter.is_set() {
+        return Err(anyhow::anyhow!("no files, --no-exists, --from-file, or filters given to pop"));
+    }
+
     let mut context = db::Context::cwd_load()?;
     let root = context.root_copy();
-    let files_iter = context.rel_to_db_list(&args.files);
+    let mut files = args.files;
+
+    if let Some(from_file) = &args.from_file {
+        files.extend(path::read_path_list(from_file)?);
+    }
+
+    let files_iter = context.rel_to_db_list(&files);
 
     let Some(coll) = context.db.collections.get_mut(&args.name) else {
-        println!("collection not found");
-        return Ok(());
+        return error::not_found("collection not found");
     };
 
-    if args.no_exists {
-        let mut updated = BTreeSet::new();
+    let mut to_remove = BTreeSet::new();
 
+    if args.no_exists {
         for file in coll.iter() {
             let full_path = root.join(&**file);
 
             if fs::check_exists(&full_path)? {
                 log::info!("file {} exists", file);
-
-                updated.insert(file.clone());
             } else {
-                log::info!("removing {}", file);
+                to_remove.insert(file.clone());
             }
         }
+    }
 
-        *coll = updated;
+    if args.filter.is_set() {
+        for key in coll.iter() {
+            if context.db.files.get(key).is_some_and(|file| args.filter.check(file)) {
+                to_remove.insert(key.clone());
+            }
+        }
     }
 
     for path_result in files_iter {
@@ -59,10 +88,32 @@ pub fn pop_coll(args: PopArgs) -> anyhow::Result<()> {
 
         let (_path, db_entry) = rel_path.into();
 
-        coll.remove(&db_entry);
+        if coll.contains(&db_entry) {
+            to_remove.insert(db_entry);
+        }
     }
 
-    context.save()?;
+    let removed = to_remove.len();
+
+    if args.dry_run {
+        for key in &to_remove {
+            println!("remove: {}", key);
+        }
+
+        log::info!("dry run, skipping save");
+
+        println!("would remove {} files", removed);
+    } else {
+        for key in &to_remove {
+            log::info!("removing {}", key);
+
+            coll.remove(key);
+        }
+
+        context.save()?;
+
+        println!("removed {} files", removed);
+    }
 
     Ok(())
 }
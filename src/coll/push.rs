@@ -1,40 +1,138 @@
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 
 use clap::Args;
 
+use crate::fs;
 use crate::logging;
+use crate::path;
 use crate::db;
+use crate::error;
+use crate::filter;
 
 #[derive(Debug, Args)]
 pub struct PushArgs {
     /// the name of the collection to push files to
     name: String,
 
+    /// reads newline separated paths to push from a file, or stdin with -
+    ///
+    /// the paths are pushed after any files given as arguments
+    #[arg(long)]
+    from_file: Option<PathBuf>,
+
+    /// only pushes files that exist
+    #[arg(long)]
+    if_exists: bool,
+
+    /// displays the files that would be added without saving them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// pushes all files in the db that match the filter
+    ///
+    /// the matching files are pushed along with any files given
+    #[command(flatten)]
+    filter: filter::FilterArgs,
+
     /// the file(s) to push
-    #[arg(trailing_var_arg(true), num_args(1..))]
+    #[arg(trailing_var_arg(true))]
     files: Vec<PathBuf>,
 }
 
+impl PushArgs {
+    /// pushes only the files in the db that match the filter
+    pub fn from_filter(name: String, if_exists: bool, dry_run: bool, filter: filter::FilterArgs) -> Self {
+        PushArgs {
+            name,
+            from_file: None,
+            if_exists,
+            dry_run,
+            filter,
+            files: Vec::new(),
+        }
+    }
+}
+
 pub fn push_coll(args: PushArgs) -> anyhow::Result<()> {
+    if args.files.is_empty() && args.from_file.is_none() && !args.filter.is_set() {
+        return Err(anyhow::anyhow!("no files, --from-file, or filters given to push"));
+    }
+
     let mut context = db::Context::cwd_load()?;
-    let files_iter = context.rel_to_db_list(&args.files);
+    let mut files = args.files;
+
+    if let Some(from_file) = &args.from_file {
+        files.extend(path::read_path_list(from_file)?);
+    }
+
+    let root = context.root_copy();
+    let files_iter = context.rel_to_db_list(&files);
 
     let Some(coll) = context.db.collections.get_mut(&args.name) else {
-        println!("collection not found");
-        return Ok(());
+        return error::not_found("collection not found");
     };
 
+    let mut to_add = BTreeSet::new();
+    let mut skipped = 0usize;
+
     for path_result in files_iter {
         let Some(rel_path) = logging::log_result(path_result) else {
             continue;
         };
 
-        let (_path, db_entry) = rel_path.into();
+        let (path, db_entry) = rel_path.into();
+
+        if args.if_exists && !fs::check_exists(&path)? {
+            log::info!("skipping {}, file does not exist", db_entry);
 
-        coll.insert(db_entry);
+            skipped += 1;
+            continue;
+        }
+
+        if !coll.contains(&db_entry) {
+            to_add.insert(db_entry);
+        }
+    }
+
+    if args.filter.is_set() {
+        for (key, file) in &context.db.files {
+            if !args.filter.check(file) || coll.contains(key) || to_add.contains(key) {
+                continue;
+            }
+
+            if args.if_exists && !fs::check_exists(&path::entry_to_path(&root, key))? {
+                log::info!("skipping {}, file does not exist", key);
+
+                skipped += 1;
+                continue;
+            }
+
+            to_add.insert(key.clone());
+        }
+    }
+
+    let added = to_add.len();
+
+    if args.dry_run {
+        for key in &to_add {
+            println!("add: {}", key);
+        }
+
+        log::info!("dry run, skipping save");
+
+        println!("would add {} files", added);
+    } else {
+        coll.extend(to_add);
+
+        context.save()?;
+
+        println!("added {} files", added);
     }
 
-    context.save()?;
+    if skipped > 0 {
+        println!("skipped {} files that do not exist", skipped);
+    }
 
     Ok(())
 }
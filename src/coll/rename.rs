@@ -0,0 +1,43 @@
+use clap::Args;
+
+use crate::db;
+
+#[derive(Debug, Args)]
+pub struct RenameArgs {
+    /// the current name of the collection
+    old: String,
+
+    /// the new name of the collection
+    new: String,
+
+    /// replaces the collection with the new name if it already exists
+    #[arg(long)]
+    overwrite: bool,
+}
+
+pub fn rename_coll(args: RenameArgs) -> anyhow::Result<()> {
+    let mut context = db::Context::cwd_load()?;
+
+    if !context.db.collections.contains_key(&args.old) {
+        return Err(anyhow::anyhow!("collection not found: {}", args.old));
+    }
+
+    if args.old == args.new {
+        println!("the old and new collection names are the same");
+        return Ok(());
+    }
+
+    if !args.overwrite && context.db.collections.contains_key(&args.new) {
+        return Err(anyhow::anyhow!("collection already exists: {}", args.new));
+    }
+
+    let files = context.db.collections.remove(&args.old).unwrap();
+
+    log::info!("renaming collection {} to {}", args.old, args.new);
+
+    context.db.collections.insert(args.new, files);
+
+    context.save()?;
+
+    Ok(())
+}
@@ -1,6 +1,13 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
 use clap::Args;
+use anyhow::Context;
 
 use crate::db;
+use crate::error;
+use crate::fs;
+use crate::path;
 
 #[derive(Debug, Args)]
 pub struct ViewArgs {
@@ -10,17 +17,60 @@ pub struct ViewArgs {
     /// will display the files attached to a collection
     #[arg(short, long)]
     files: bool,
+
+    /// outputs the collections and their files as json
+    #[arg(long, conflicts_with("missing"))]
+    json: bool,
+
+    /// only displays files in a collection that do not exist
+    #[arg(long)]
+    missing: bool,
+}
+
+/// displays the files in a collection that do not exist on the file system
+fn view_missing(name: &str, files: &BTreeSet<Box<str>>, root: &Path) -> anyhow::Result<usize> {
+    let mut missing = Vec::new();
+
+    for file in files {
+        if !fs::check_exists(&path::entry_to_path(root, file))? {
+            missing.push(file);
+        }
+    }
+
+    println!("{}: {} missing files", name, missing.len());
+
+    for file in &missing {
+        println!("{}", file);
+    }
+
+    Ok(missing.len())
 }
 
 pub fn view_coll(args: ViewArgs) -> anyhow::Result<()> {
-    let context = db::Context::cwd_load()?;
+    let context = db::Context::load_readonly()?;
 
     if let Some(lookup) = args.name {
         let Some(files) = context.db.collections.get(&lookup) else {
-            println!("collection not found");
-            return Ok(());
+            return error::not_found("collection not found");
         };
 
+        if args.json {
+            let single = BTreeMap::from([(&lookup, files)]);
+
+            serde_json::to_writer(std::io::stdout(), &single)
+                .context("failed writing collection to output")?;
+
+            println!();
+
+            return Ok(());
+        }
+
+        if args.missing {
+            view_missing(&lookup, files, context.root())?;
+
+            return Ok(());
+        }
+
         println!("{}: {} files", lookup, files.len());
 
         if args.files {
@@ -29,6 +79,29 @@ pub fn view_coll(args: ViewArgs) -> anyhow::Result<()> {
             }
         }
     } else {
+        if args.json {
+            serde_json::to_writer(std::io::stdout(), &context.db.collections)
+                .context("failed writing collections to output")?;
+
+            println!();
+
+            return Ok(());
+        }
+
+        if args.missing {
+            let mut total = 0;
+
+            for (name, files) in &context.db.collections {
+                total += view_missing(name, files, context.root())?;
+            }
+
+            println!("Total: {} missing files", total);
+
+            return Ok(());
+        }
+
+        let mut distinct = BTreeSet::new();
+
         for (name, files) in &context.db.collections {
             println!("{}: {} files", name, files.len());
 
@@ -37,7 +110,11 @@ pub fn view_coll(args: ViewArgs) -> anyhow::Result<()> {
                     println!("{}", file);
                 }
             }
+
+            distinct.extend(files.iter());
         }
+
+        println!("Total: {} distinct files", distinct.len());
     }
 
     Ok(())
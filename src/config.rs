@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use anyhow::Context as _;
+use serde::Deserialize;
+
+use crate::db;
+use crate::get;
+
+pub const CONFIG_NAME: &str = "config.toml";
+
+/// optional settings stored in `.fsm/config.toml`
+///
+/// values are only used when the corresponding flag is not given on the
+/// command line. unknown keys are reported and ignored
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// the format used by db dump
+    pub dump_format: Option<db::Format>,
+
+    /// the sort used by get
+    pub sort_by: Option<Vec<get::SortBy>>,
+
+    /// the number of backups to keep
+    pub backup_retention: Option<usize>,
+
+    /// tags that are displayed first by get in the order given
+    pub pinned_tags: Vec<String>,
+
+    /// the order that db files are checked for when more than one exists
+    pub db_format_order: Vec<db::Format>,
+
+    /// appends a line to `.fsm/audit.log` for each change to the db
+    pub audit: bool,
+}
+
+impl Config {
+    /// loads the config from the given .fsm directory
+    ///
+    /// if the config is missing then the defaults are used. if it cannot be
+    /// read or parsed then a warning is displayed and the defaults are used.
+    /// unknown keys only display a warning
+    pub fn load(fsm_dir: &Path) -> Self {
+        let config_path = fsm_dir.join(CONFIG_NAME);
+
+        let contents = match std::fs::read_to_string(&config_path) {
+            Ok(v) => v,
+            Err(err) => {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    eprintln!("warning: failed reading config {}: {}", config_path.display(), err);
+                }
+
+                return Config::default();
+            }
+        };
+
+        log::info!("reading {}", config_path.display());
+
+        let mut unknown = Vec::new();
+
+        let result = serde_ignored::deserialize(toml::Deserializer::new(&contents), |key| {
+            unknown.push(key.to_string());
+        });
+
+        for key in unknown {
+            eprintln!("warning: unknown key in config {}: {}", config_path.display(), key);
+        }
+
+        match result {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("warning: invalid config {}: {}", config_path.display(), err);
+
+                Config::default()
+            }
+        }
+    }
+}
+
+/// reads only the db format order from the config of the given .fsm directory
+///
+/// this is used while searching for a db so any problems with the config are
+/// ignored here and reported when the config is loaded with the db
+pub fn read_format_order(fsm_dir: &Path) -> Vec<db::Format> {
+    std::fs::read_to_string(fsm_dir.join(CONFIG_NAME))
+        .ok()
+        .and_then(|contents| toml::from_str::<Config>(&contents).ok())
+        .map(|config| config.db_format_order)
+        .unwrap_or_default()
+}
+
+/// stores the sort used by get in the config of the given .fsm directory
+///
+/// other values and comments in the config are kept
+pub fn save_sort_by(fsm_dir: &Path, sort_by: &[get::SortBy]) -> anyhow::Result<()> {
+    let config_path = fsm_dir.join(CONFIG_NAME);
+
+    let contents = match std::fs::read_to_string(&config_path) {
+        Ok(v) => v,
+        Err(err) => if err.kind() == std::io::ErrorKind::NotFound {
+            String::new()
+        } else {
+            return Err(err).with_context(|| format!("failed reading config {}", config_path.display()));
+        }
+    };
+
+    let mut document: toml_edit::DocumentMut = contents.parse()
+        .with_context(|| format!("invalid config {}", config_path.display()))?;
+
+    let list: toml_edit::Array = sort_by.iter()
+        .map(get::SortBy::to_string)
+        .collect();
+
+    document["sort_by"] = toml_edit::value(list);
+
+    std::fs::write(&config_path, document.to_string())
+        .with_context(|| format!("failed writing config {}", config_path.display()))
+}
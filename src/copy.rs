@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use clap::Args;
+
+use crate::db::{self, MetaContainer as _};
+use crate::r#move::{get_dst_entry, merge_tags, merge_comment, OnConflict};
+
+#[derive(Debug, Args)]
+pub struct CopyArgs {
+    /// copies only tags
+    #[arg(long, conflicts_with("comment"))]
+    tags: bool,
+
+    /// copies only the comment
+    #[arg(long, conflicts_with("tags"))]
+    comment: bool,
+
+    /// copies data from the db to the destination
+    #[arg(long, conflicts_with_all(["from", "to_self"]))]
+    from_self: bool,
+
+    /// the source file item
+    #[arg(short, long, required_unless_present("from_self"))]
+    from: Option<PathBuf>,
+
+    /// checks to see if the destination exists
+    #[arg(long)]
+    exists: bool,
+
+    /// copies data to the db from the source
+    #[arg(long, conflicts_with_all(["to", "from_self"]))]
+    to_self: bool,
+
+    /// the destination file item
+    #[arg(short, long, required_unless_present("to_self"))]
+    to: Option<PathBuf>,
+
+    /// what to do when the destination already has a tag or comment from
+    /// the source
+    #[arg(long, value_enum, default_value_t)]
+    on_conflict: OnConflict,
+}
+
+fn get_src_entry(context: &db::Context, path: PathBuf) -> anyhow::Result<&db::FileData> {
+    let (src_path, src_entry) = context.rel_to_db(path)?.into();
+
+    log::info!("copying from entry: {}", src_entry);
+
+    context.db.files.get(&src_entry)
+        .with_context(|| format!("source not found in db: {}", src_path.display()))
+}
+
+pub fn copy_data(args: CopyArgs) -> anyhow::Result<()> {
+    let mut context = db::Context::cwd_load()?;
+
+    let (src_tags, src_comment) = if let Some(from) = args.from {
+        let found = get_src_entry(&context, from)?;
+
+        (found.tags.clone(), found.comment.clone())
+    } else {
+        log::info!("copying data from db");
+
+        (context.db.tags.clone(), context.db.comment.clone())
+    };
+
+    if let Some(to) = args.to {
+        let found = get_dst_entry(&mut context, to, args.exists)?;
+
+        if !args.comment {
+            merge_tags(&mut found.tags, src_tags, args.on_conflict)?;
+        }
+
+        if !args.tags {
+            merge_comment(&mut found.comment, src_comment, args.on_conflict)?;
+        }
+    } else {
+        log::info!("updating db");
+
+        context.db.update_ts();
+
+        if !args.comment {
+            merge_tags(&mut context.db.tags, src_tags, args.on_conflict)?;
+        }
+
+        if !args.tags {
+            merge_comment(&mut context.db.comment, src_comment, args.on_conflict)?;
+        }
+    }
+
+    context.save()?;
+
+    Ok(())
+}
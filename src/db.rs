@@ -1,16 +1,24 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound;
 use std::path::{PathBuf, Path};
-use std::io::{BufWriter, BufReader};
+use std::io::{BufWriter, BufReader, Read, Write};
 use std::default::Default;
 use std::ffi::OsStr;
 use std::fs::OpenOptions;
 use std::fmt::Debug;
+use std::sync::OnceLock;
 
 use serde::{Serialize, Deserialize};
 use anyhow::Context as _;
 use clap::{Args, Subcommand, ValueEnum};
-
-use crate::fs::get_metadata;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use path_absolutize::Absolutize as _;
+
+use crate::audit;
+use crate::config::{self, Config};
+use crate::fs::{get_metadata, check_exists};
 use crate::tags;
 use crate::path;
 use crate::time;
@@ -18,6 +26,15 @@ use crate::time;
 pub mod init;
 pub mod dump;
 pub mod drop;
+pub mod gc;
+pub mod stats;
+pub mod verify;
+pub mod merge;
+pub mod export;
+pub mod import;
+pub mod location;
+pub mod clean;
+pub mod history;
 
 #[derive(Debug, Args)]
 pub struct DbArgs {
@@ -35,6 +52,34 @@ enum ManageCmd {
 
     /// drops a db and fsm directory
     Drop(drop::DropArgs),
+
+    /// removes entries and collection references for files that no longer
+    /// exist
+    Gc(gc::GcArgs),
+
+    /// displays an overview of the contents of the db
+    Stats(stats::StatsArgs),
+
+    /// checks the db for problems
+    Verify(verify::VerifyArgs),
+
+    /// merges another db into the current db
+    Merge(merge::MergeArgs),
+
+    /// writes the db to a file outside of the .fsm directory
+    Export(export::ExportArgs),
+
+    /// loads a previously exported db into the current db
+    Import(import::ImportArgs),
+
+    /// displays the location of the db that will be used
+    Path(location::PathArgs),
+
+    /// removes db files in the .fsm directory that are not being used
+    Clean(clean::CleanArgs),
+
+    /// lists entries that were recently created or updated
+    History(history::HistoryArgs),
 }
 
 pub fn manage(args: DbArgs) -> anyhow::Result<()> {
@@ -42,21 +87,90 @@ pub fn manage(args: DbArgs) -> anyhow::Result<()> {
         ManageCmd::Init(init_args) => init::init_db(init_args),
         ManageCmd::Dump(dump_args) => dump::dump_db(dump_args),
         ManageCmd::Drop(drop_args) => drop::drop_db(drop_args),
+        ManageCmd::Gc(gc_args) => gc::gc_db(gc_args),
+        ManageCmd::Stats(stats_args) => stats::stats_db(stats_args),
+        ManageCmd::Verify(verify_args) => verify::verify_db(verify_args),
+        ManageCmd::Merge(merge_args) => merge::merge_db(merge_args),
+        ManageCmd::Export(export_args) => export::export_db(export_args),
+        ManageCmd::Import(import_args) => import::import_db(import_args),
+        ManageCmd::Path(path_args) => location::path_db(path_args),
+        ManageCmd::Clean(clean_args) => clean::clean_db(clean_args),
+        ManageCmd::History(history_args) => history::history_db(history_args),
     }
 }
 
+static DB_PATH: OnceLock<Box<Path>> = OnceLock::new();
+
+/// sets an explicit db path to use instead of searching from the current
+/// working directory
+pub fn set_db_path(path: &Path) {
+    let _ = DB_PATH.set(path.into());
+}
+
+static ASSUME_ROOT: OnceLock<Box<Path>> = OnceLock::new();
+
+/// sets the root directory to use instead of the parent of the .fsm
+/// directory
+///
+/// the root must be the current working directory or one of its parents
+pub fn set_assume_root(given: &Path) -> anyhow::Result<()> {
+    let root = given.absolutize_from(path::get_cwd())
+        .with_context(|| format!("failed to resolve root: {}", given.display()))?;
+
+    if !path::get_cwd().starts_with(&root) {
+        return Err(anyhow::anyhow!(
+            "the assumed root must contain the current directory: {}",
+            root.display()
+        ));
+    }
+
+    let _ = ASSUME_ROOT.set(root.into());
+
+    Ok(())
+}
+
+static NO_ANCESTOR_SEARCH: OnceLock<bool> = OnceLock::new();
+
+const NO_ANCESTOR_SEARCH_ENV: &str = "FSM_NO_ANCESTOR_SEARCH";
+
+/// a file that stops the search for a db from continuing to parent
+/// directories
+pub const ROOT_MARKER_NAME: &str = ".fsm-root";
+
+/// only looks for a db in the current working directory instead of also
+/// searching parent directories
+///
+/// also enabled if `FSM_NO_ANCESTOR_SEARCH` is set
+pub fn set_no_ancestor_search(value: bool) {
+    let value = value || std::env::var_os(NO_ANCESTOR_SEARCH_ENV).is_some();
+
+    let _ = NO_ANCESTOR_SEARCH.set(value);
+}
+
+fn no_ancestor_search() -> bool {
+    NO_ANCESTOR_SEARCH.get().copied().unwrap_or(false)
+}
+
 type DbPath = Box<Path>;
 type RootPath = Box<Path>;
+type FsmDirPath = Box<Path>;
 
 const DB_PRETTY_JSON_NAME: &str = "db.pretty.json";
 const DB_JSON_NAME: &str = "db.json";
 const DB_BINARY_NAME: &str = "db.bincode";
+const DB_BINARY_GZ_NAME: &str = "db.bincode.gz";
 
-#[derive(Debug, Clone, ValueEnum)]
+const BACKUP_DIR_NAME: &str = "backups";
+const LAST_RUN_NAME: &str = "last_get_run";
+pub const BACKUP_RETENTION: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Format {
     JsonPretty,
     Json,
     Binary,
+    BinaryGz,
 }
 
 impl Format {
@@ -65,16 +179,176 @@ impl Format {
             Format::JsonPretty => OsStr::new(DB_PRETTY_JSON_NAME),
             Format::Json => OsStr::new(DB_JSON_NAME),
             Format::Binary => OsStr::new(DB_BINARY_NAME),
+            Format::BinaryGz => OsStr::new(DB_BINARY_GZ_NAME),
         }
     }
+
+    pub fn from_file_name(name: &OsStr) -> Option<Self> {
+        FORMAT_LIST.into_iter()
+            .find(|format| format.file_name() == name)
+    }
+
+    /// attempts to determine the format from the name of an arbitrary file
+    ///
+    /// known db file names are checked first and then the extension. files
+    /// that cannot be determined are assumed to be binary
+    pub fn from_path(path: &Path) -> Self {
+        if let Some(found) = path.file_name().and_then(Self::from_file_name) {
+            return found;
+        }
+
+        match path.extension().and_then(OsStr::to_str) {
+            Some("json") => Format::Json,
+            Some("gz") => Format::BinaryGz,
+            _ => Format::Binary,
+        }
+    }
+
+    /// deserializes a db from the given reader
+    pub fn read_db<R>(&self, reader: R) -> anyhow::Result<Db>
+    where
+        R: Read
+    {
+        let db = match self {
+            Format::JsonPretty |
+            Format::Json => serde_json::from_reader(reader)
+                .context("failed deserializing db json")?,
+            Format::Binary => bincode::deserialize_from(reader)
+                .context("failed deserializing db binary")?,
+            Format::BinaryGz => bincode::deserialize_from(GzDecoder::new(reader))
+                .context("failed deserializing db binary gz")?,
+        };
+
+        Ok(db)
+    }
+
+    /// serializes a db to the given writer
+    pub fn write_db<W>(&self, mut writer: W, db: &Db) -> anyhow::Result<()>
+    where
+        W: Write
+    {
+        match self {
+            Format::JsonPretty => serde_json::to_writer_pretty(&mut writer, db)
+                .context("failed serializing db json")?,
+            Format::Json => serde_json::to_writer(&mut writer, db)
+                .context("failed serializing db json")?,
+            Format::Binary => bincode::serialize_into(&mut writer, db)
+                .context("failed serializing db binary")?,
+            Format::BinaryGz => {
+                let mut encoder = GzEncoder::new(&mut writer, Compression::default());
+
+                bincode::serialize_into(&mut encoder, db)
+                    .context("failed serializing db binary gz")?;
+
+                encoder.finish()
+                    .context("failed compressing db binary gz")?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
-pub const FORMAT_LIST: [Format; 3] = [
+pub const FORMAT_LIST: [Format; 4] = [
     Format::JsonPretty,
     Format::Json,
     Format::Binary,
+    Format::BinaryGz,
 ];
 
+const FORMAT_ORDER_ENV: &str = "FSM_DB_FORMAT_ORDER";
+
+/// the order that db files are checked for in the given .fsm directory
+///
+/// the order can be set with a comma separated list of formats in
+/// `FSM_DB_FORMAT_ORDER` or the `db_format_order` config value, with the env
+/// taking priority. formats not listed are checked after in the default order
+fn format_order(fsm_dir: &Path) -> anyhow::Result<Vec<Format>> {
+    let given = if let Some(value) = std::env::var_os(FORMAT_ORDER_ENV) {
+        let value = value.to_string_lossy();
+        let mut list = Vec::new();
+
+        for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let format = Format::from_str(name, true)
+                .map_err(|_| anyhow::anyhow!("unknown db format in {}: {}", FORMAT_ORDER_ENV, name))?;
+
+            list.push(format);
+        }
+
+        list
+    } else {
+        config::read_format_order(fsm_dir)
+    };
+
+    let mut rtn = Vec::with_capacity(FORMAT_LIST.len());
+
+    for format in given.into_iter().chain(FORMAT_LIST) {
+        if !rtn.contains(&format) {
+            rtn.push(format);
+        }
+    }
+
+    Ok(rtn)
+}
+
+/// checks if the data of two containers differs
+pub fn entry_changed(current: &dyn MetaContainer, previous: &dyn MetaContainer) -> bool {
+    current.tags() != previous.tags() ||
+        current.comment() != previous.comment() ||
+        current.created() != previous.created() ||
+        current.updated() != previous.updated()
+}
+
+/// folds the case of all file entries and collection members
+///
+/// entries stored before case folding was added are moved to their folded
+/// name so they can be found again and are written back on the next save. if
+/// two entries fold to the same name then the tags and comment of the first
+/// are kept and any missing from it are taken from the others
+#[cfg(windows)]
+fn fold_entries(db: &mut Db) {
+    use std::collections::btree_map::Entry;
+
+    let mut folded: BTreeMap<Box<str>, FileData> = BTreeMap::new();
+
+    for (key, data) in std::mem::take(&mut db.files) {
+        let folded_key: Box<str> = path::fold_case(String::from(key.clone())).into();
+
+        match folded.entry(folded_key) {
+            Entry::Vacant(vacant) => {
+                vacant.insert(data);
+            }
+            Entry::Occupied(mut occupied) => {
+                eprintln!("warning: merging db entry {} into {}", key, occupied.key());
+
+                let existing = occupied.get_mut();
+
+                for (tag, value) in data.tags {
+                    existing.tags.entry(tag).or_insert(value);
+                }
+
+                if existing.comment.is_none() {
+                    existing.comment = data.comment;
+                }
+            }
+        }
+    }
+
+    db.files = folded;
+
+    for coll in db.collections.values_mut() {
+        *coll = std::mem::take(coll)
+            .into_iter()
+            .map(|key| path::fold_case(String::from(key)).into())
+            .collect();
+    }
+}
+
+/// only windows is case insensitive so entries on other platforms are left
+/// as is
+#[cfg(not(windows))]
+fn fold_entries(_db: &mut Db) {}
+
 pub trait MetaContainer: Debug {
     fn created(&self) -> &time::DateTime;
     fn updated(&self) -> Option<&time::DateTime>;
@@ -90,7 +364,8 @@ pub trait MetaContainer: Debug {
     fn take_tags_comment(&mut self) -> (tags::TagsMap, Option<String>);
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct FileData {
     pub tags: tags::TagsMap,
     pub comment: Option<String>,
@@ -127,7 +402,7 @@ impl MetaContainer for FileData {
     }
 
     fn comment(&self) -> Option<&str> {
-        self.comment.as_ref().map(|v| v.as_str())
+        self.comment.as_deref()
     }
 
     fn update_ts(&mut self) {
@@ -147,7 +422,12 @@ impl MetaContainer for FileData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// the data stored in a db file
+///
+/// unknown fields are rejected so that typos in a hand edited db are reported
+/// instead of silently ignored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Db {
     pub files: BTreeMap<Box<str>, FileData>,
     pub collections: BTreeMap<String, BTreeSet<Box<str>>>,
@@ -171,6 +451,23 @@ impl Default for Db {
     }
 }
 
+impl Db {
+    /// iterates all file entries that are the given directory entry or are
+    /// contained within it
+    ///
+    /// an empty entry is the root of the db and will match everything
+    pub fn files_under<'a, 'b>(&'a self, dir: &'b str) -> impl Iterator<Item = (&'a Box<str>, &'a FileData)> + 'b
+    where
+        'a: 'b
+    {
+        self.files.range::<str, _>((Bound::Included(dir), Bound::Unbounded))
+            .take_while(move |(key, _)| key.starts_with(dir))
+            .filter(move |(key, _)| {
+                dir.is_empty() || key.len() == dir.len() || key[dir.len()..].starts_with('/')
+            })
+    }
+}
+
 impl MetaContainer for Db {
     fn created(&self) -> &time::DateTime {
         &self.created
@@ -189,7 +486,7 @@ impl MetaContainer for Db {
     }
 
     fn comment(&self) -> Option<&str> {
-        self.comment.as_ref().map(|v| v.as_str())
+        self.comment.as_deref()
     }
 
     fn update_ts(&mut self) {
@@ -209,12 +506,25 @@ impl MetaContainer for Db {
     }
 }
 
+/// the results of searching for a db
+#[derive(Default)]
+struct Search {
+    found: Option<(DbPath, Format)>,
+    searched: Vec<PathBuf>,
+    empty: Vec<PathBuf>,
+}
+
 #[derive(Debug)]
 pub struct Context {
     format: Format,
     pub db: Db,
     path: DbPath,
+    fsm_dir: FsmDirPath,
     root: RootPath,
+    config: Config,
+    readonly: bool,
+    /// the db as it was loaded, only kept when the audit log is enabled
+    loaded: Option<Db>,
 }
 
 impl Context {
@@ -223,13 +533,19 @@ impl Context {
         P: Into<DbPath>
     {
         let path = path.into();
-        let root = Self::get_root(&path);
+        let fsm_dir = Self::get_fsm_dir(&path)?;
+        let root = Self::get_root(&path)?;
+        let config = Config::load(&fsm_dir);
 
         let rtn = Context {
             format,
             db: Db::default(),
             path,
-            root
+            fsm_dir,
+            root,
+            config,
+            readonly: false,
+            loaded: None,
         };
 
         rtn.write_file(true)?;
@@ -237,49 +553,176 @@ impl Context {
         Ok(rtn)
     }
 
-    fn get_root(path: &Path) -> RootPath {
+    /// the .fsm directory containing the given db file
+    fn get_fsm_dir(path: &Path) -> anyhow::Result<FsmDirPath> {
         path.parent()
-            .unwrap()
-            .parent()
-            .unwrap()
-            .into()
+            .filter(|fsm_dir| !fsm_dir.as_os_str().is_empty())
+            .map(Into::into)
+            .ok_or_else(|| anyhow::anyhow!(
+                "unexpected db location, expected the db file to be in <root>/.fsm: {}",
+                path.display()
+            ))
     }
 
+    /// determines the root directory for the given db file
+    ///
+    /// the db file is expected to be in a `.fsm` directory that is contained
+    /// in the root. the assumed root is used if one has been set
+    pub fn get_root(path: &Path) -> anyhow::Result<RootPath> {
+        let fsm_dir = Self::get_fsm_dir(path)?;
+
+        if let Some(root) = ASSUME_ROOT.get() {
+            return Ok(root.clone());
+        }
+
+        fsm_dir.parent()
+            .filter(|root| !root.as_os_str().is_empty())
+            .map(Into::into)
+            .ok_or_else(|| anyhow::anyhow!(
+                "unexpected db location, expected the db file to be in <root>/.fsm: {}",
+                path.display()
+            ))
+    }
+
+    /// searches the given path and its parents for a db
+    ///
+    /// the search will not continue past a directory containing a
+    /// `.fsm-root` file or past the given path if ancestor search is
+    /// disabled
     pub fn find_file<P>(ref_path: P) -> anyhow::Result<Option<(DbPath, Format)>>
     where
         P: AsRef<Path>
     {
-        let ref_path = ref_path.as_ref();
+        Ok(Self::search(ref_path.as_ref())?.found)
+    }
+
+    fn search(ref_path: &Path) -> anyhow::Result<Search> {
+        let mut rtn = Search::default();
 
         for ancestor in ref_path.ancestors() {
             let fsm_dir = ancestor.join(".fsm");
 
-            let Some(metadata) = get_metadata(&fsm_dir)
-                .context("io error when checkign for .fsm directory")? else {
+            rtn.searched.push(ancestor.to_path_buf());
+
+            let metadata = get_metadata(&fsm_dir)
+                .context("io error when checkign for .fsm directory")?;
+
+            if metadata.is_some_and(|m| m.is_dir()) {
+                if let Some(found) = Self::find_in_dir(&fsm_dir)? {
+                    rtn.found = Some(found);
+
+                    return Ok(rtn);
+                }
+
+                rtn.empty.push(fsm_dir);
+            }
+
+            if no_ancestor_search() {
+                log::info!("ancestor search disabled");
+
+                break;
+            }
+
+            if check_exists(&ancestor.join(ROOT_MARKER_NAME))
+                .context("io error when checking for root marker")? {
+                log::info!("found root marker in {}", ancestor.display());
+
+                break;
+            }
+        }
+
+        Ok(rtn)
+    }
+
+    /// looks for a db file in the given .fsm directory
+    fn find_in_dir(fsm_dir: &Path) -> anyhow::Result<Option<(DbPath, Format)>> {
+        Ok(Self::find_all_in_dir(fsm_dir)?.into_iter().next())
+    }
+
+    /// finds all db files in the given .fsm directory
+    ///
+    /// the files are in the order that they would be selected when loading
+    /// the db, so the first is the one that is used
+    pub fn find_all_in_dir(fsm_dir: &Path) -> anyhow::Result<Vec<(DbPath, Format)>> {
+        let mut rtn = Vec::new();
+
+        for format in &format_order(fsm_dir)? {
+            let db_file = fsm_dir.join(format.file_name());
+
+            let Some(metadata) = get_metadata(&db_file)
+                .context("io error when checking for db file")? else {
                 continue;
             };
 
-            if !metadata.is_dir() {
-                continue;
+            if metadata.is_file() {
+                rtn.push((db_file.into(), format.clone()));
             }
+        }
 
-            for format in &FORMAT_LIST {
-                let db_file = fsm_dir.join(format.file_name());
+        Ok(rtn)
+    }
 
-                let Some(metadata) = get_metadata(&db_file)
-                    .context("io error when checking for db file")? else {
-                    continue;
-                };
+    /// finds any db files next to the given db file that are ignored
+    pub fn find_ignored(path: &Path) -> anyhow::Result<Vec<(DbPath, Format)>> {
+        let Some(fsm_dir) = path.parent() else {
+            return Ok(Vec::new());
+        };
 
-                if !metadata.is_file() {
-                    continue;
-                }
+        let mut rtn = Self::find_all_in_dir(fsm_dir)?;
+        rtn.retain(|(found, _)| **found != *path);
 
-                return Ok(Some((db_file.into(), format.clone())));
-            }
+        Ok(rtn)
+    }
+
+    /// resolves the db file and format from the given path
+    ///
+    /// the path can be a db file, a .fsm directory, or a directory containing
+    /// a .fsm directory. relative paths are resolved from the current working
+    /// directory
+    pub fn resolve_path<P>(given: P) -> anyhow::Result<(DbPath, Format)>
+    where
+        P: AsRef<Path>
+    {
+        let given = given.as_ref();
+        let full = given.absolutize_from(path::get_cwd())
+            .with_context(|| format!("failed to resolve db path: {}", given.display()))?;
+
+        let Some(metadata) = get_metadata(&full)
+            .context("io error when checking db path")? else {
+            return Err(anyhow::anyhow!("db path does not exist: {}", full.display()));
+        };
+
+        if metadata.is_file() {
+            let Some(format) = full.file_name().and_then(Format::from_file_name) else {
+                return Err(anyhow::anyhow!("unknown db file name: {}", full.display()));
+            };
+
+            return Ok((full.into(), format));
         }
 
-        Ok(None)
+        let fsm_dir = if full.file_name() == Some(OsStr::new(".fsm")) {
+            full.to_path_buf()
+        } else {
+            full.join(".fsm")
+        };
+
+        let Some(found) = Self::find_in_dir(&fsm_dir)? else {
+            return Err(anyhow::anyhow!("no db found in: {}", fsm_dir.display()));
+        };
+
+        Ok(found)
+    }
+
+    /// loads a db from the given path
+    ///
+    /// see [`Context::resolve_path`] for the accepted paths
+    pub fn load_from<P>(given: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path>
+    {
+        let (path, format) = Self::resolve_path(given)?;
+
+        Self::read_file(path, format)
     }
 
     fn read_file(path: Box<Path>, format: Format) -> anyhow::Result<Self> {
@@ -289,38 +732,165 @@ impl Context {
             .read(true)
             .open(&path)
             .with_context(|| format!("failed reading db: {}", path.display()))?;
+        let file_len = file.metadata()
+            .with_context(|| format!("failed reading db metadata: {}", path.display()))?
+            .len();
         let reader = BufReader::new(file);
 
         let start = std::time::Instant::now();
 
-        let db = match &format {
-            Format::JsonPretty |
-            Format::Json => serde_json::from_reader(reader)
-                .with_context(|| format!("failed deserializing db json: {}", path.display()))?,
-            Format::Binary => bincode::deserialize_from(reader)
-                .with_context(|| format!("failed deserializing db binary: {}", path.display()))?
-        };
+        let mut db = format.read_db(reader)
+            .with_context(|| format!("failed loading db: {}", path.display()))?;
+
+        fold_entries(&mut db);
+
+        if let Format::BinaryGz = &format {
+            log::info!("db parse time: {:?} compressed size: {} bytes", start.elapsed(), file_len);
+        } else {
+            log::info!("db parse time: {:?}", start.elapsed());
+        }
 
-        log::info!("db parse time: {:?}", start.elapsed());
+        let fsm_dir = Self::get_fsm_dir(&path)?;
+        let root = Self::get_root(&path)?;
+        let config = Config::load(&fsm_dir);
 
-        let root = Self::get_root(&path);
+        let loaded = config.audit.then(|| db.clone());
 
         Ok(Context {
             format,
             db,
             path,
+            fsm_dir,
             root,
+            config,
+            readonly: false,
+            loaded,
         })
     }
 
+    /// finds the db file for the current working directory without loading
+    /// it
+    ///
+    /// if a db path has been set then it will be used instead of searching
+    pub fn cwd_find() -> anyhow::Result<(DbPath, Format)> {
+        if let Some(db_path) = DB_PATH.get() {
+            return Self::resolve_path(db_path);
+        }
+
+        let search = Self::search(path::get_cwd())?;
+
+        if let Some(found) = search.found {
+            return Ok(found);
+        }
+
+        if let Some(empty) = search.empty.first() {
+            return Err(anyhow::anyhow!(
+                "found {} but it does not contain a db file. run `fsm db init` in {} to create one",
+                empty.display(),
+                empty.parent().unwrap_or(empty).display()
+            ));
+        }
+
+        let searched = search.searched.iter()
+            .map(|dir| format!("\n    {}", dir.display()))
+            .collect::<String>();
+
+        Err(anyhow::anyhow!(
+            "no .fsm directory found. run `fsm db init` to create a db in the current directory. searched:{}",
+            searched
+        ))
+    }
+
     pub fn cwd_load() -> anyhow::Result<Self> {
-        let Some((path, format)) = Self::find_file(path::get_cwd())? else {
-            return Err(anyhow::anyhow!("no db found"));
-        };
+        let (path, format) = Self::cwd_find()?;
 
         Self::read_file(path, format)
     }
 
+    /// loads the db for the current working directory for commands that will
+    /// not update it
+    ///
+    /// the db file is only opened for reading and the returned context
+    /// cannot be saved
+    pub fn load_readonly() -> anyhow::Result<Self> {
+        Ok(Self::cwd_load()?.readonly())
+    }
+
+    /// marks the context as read only, any attempt to save it will fail
+    pub fn readonly(mut self) -> Self {
+        self.readonly = true;
+        self
+    }
+
+    /// loads the db for the current working directory, creating a new db in
+    /// the current working directory if one is not found
+    pub fn cwd_load_or_init(format: Format) -> anyhow::Result<Self> {
+        if DB_PATH.get().is_some() || Self::find_file(path::get_cwd())?.is_some() {
+            return Self::cwd_load();
+        }
+
+        let fsm_dir = path::get_cwd().join(".fsm");
+
+        if !check_exists(&fsm_dir)? {
+            log::info!("creating .fsm directory");
+
+            std::fs::create_dir(&fsm_dir)
+                .context("failed to create .fsm directory")?;
+        }
+
+        println!("initialized db in {}", fsm_dir.display());
+
+        Self::create(fsm_dir.join(format.file_name()), format)
+    }
+
+    fn tmp_path(&self) -> PathBuf {
+        let mut tmp = self.path.as_os_str().to_owned();
+        tmp.push(".tmp");
+
+        tmp.into()
+    }
+
+    fn write_tmp(&self, tmp_path: &Path) -> anyhow::Result<()> {
+        let file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(tmp_path)
+            .with_context(|| format!("failed to open temp db file: {}", tmp_path.display()))?;
+        let mut writer = BufWriter::new(file);
+
+        let start = std::time::Instant::now();
+
+        self.format.write_db(&mut writer, &self.db)
+            .with_context(|| format!("failed saving db: {}", self.path.display()))?;
+
+        let file = writer.into_inner()
+            .map_err(|err| err.into_error())
+            .with_context(|| format!("failed writing temp db file: {}", tmp_path.display()))?;
+
+        file.sync_all()
+            .with_context(|| format!("failed syncing temp db file: {}", tmp_path.display()))?;
+
+        // sizing the uncompressed db walks the whole structure again so only
+        // do it when the log will actually be displayed
+        if let (Format::BinaryGz, true) = (&self.format, log::log_enabled!(log::Level::Info)) {
+            let elapsed = start.elapsed();
+            let compressed = file.metadata()
+                .with_context(|| format!("failed reading temp db metadata: {}", tmp_path.display()))?
+                .len();
+            let uncompressed = bincode::serialized_size(&self.db)
+                .with_context(|| format!("failed sizing db binary: {}", self.path.display()))?;
+
+            log::info!("db save time: {:?} compressed {} -> {} bytes", elapsed, uncompressed, compressed);
+        } else {
+            log::info!("db save time: {:?}", start.elapsed());
+        }
+
+        Ok(())
+    }
+
+    /// writes the db to a temp file in the same directory and then renames it
+    /// over the db file so an interrupted write never leaves a partial db
     fn write_file(&self, create: bool) -> anyhow::Result<()> {
         if create {
             log::info!("creating {}", self.path.display());
@@ -328,32 +898,114 @@ impl Context {
             log::info!("writing {}", self.path.display());
         }
 
-        let file = OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .create(create)
-            .open(&self.path)
-            .with_context(|| format!("failed to open db file: {}", self.path.display()))?;
-        let writer = BufWriter::new(file);
+        let tmp_path = self.tmp_path();
 
-        let start = std::time::Instant::now();
+        if let Err(err) = self.write_tmp(&tmp_path) {
+            if let Err(rm_err) = std::fs::remove_file(&tmp_path) {
+                log::info!("failed to remove temp db file: {}", rm_err);
+            }
+
+            return Err(err);
+        }
+
+        std::fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("failed to replace db file: {}", self.path.display()))?;
+
+        Ok(())
+    }
+
+    /// the .fsm directory containing the db file
+    pub fn fsm_dir(&self) -> &Path {
+        &self.fsm_dir
+    }
+
+    pub fn backup_dir(&self) -> PathBuf {
+        self.fsm_dir.join(BACKUP_DIR_NAME)
+    }
+
+    /// the file storing the time of the last `get --since-last-run`
+    pub fn last_run_path(&self) -> PathBuf {
+        self.fsm_dir.join(LAST_RUN_NAME)
+    }
+
+    /// copies the current db file into the backups directory and prunes the
+    /// oldest backups beyond the retention count
+    ///
+    /// the timestamp uses the basic ISO 8601 form so that the file name is
+    /// valid on all systems and sorts chronologically
+    fn backup(&self) -> anyhow::Result<()> {
+        if !check_exists(&self.path)? {
+            return Ok(());
+        }
+
+        let backup_dir = self.backup_dir();
+
+        if !check_exists(&backup_dir)? {
+            std::fs::create_dir(&backup_dir)
+                .with_context(|| format!("failed to create backup directory: {}", backup_dir.display()))?;
+        }
+
+        let name = self.format.file_name()
+            .to_str()
+            .unwrap();
+        let ts = time::datetime_now().format("%Y%m%dT%H%M%S%.6fZ");
+        let backup_path = backup_dir.join(format!("{name}.{ts}"));
+
+        log::info!("backing up db to {}", backup_path.display());
+
+        std::fs::copy(&self.path, &backup_path)
+            .with_context(|| format!("failed to backup db file: {}", backup_path.display()))?;
+
+        let prefix = format!("{name}.");
+        let mut existing = Vec::new();
+
+        for entry in std::fs::read_dir(&backup_dir).context("failed to read backup directory")? {
+            let entry = entry.context("failed to read backup directory entry")?;
+            let file_name = entry.file_name();
 
-        match &self.format {
-            Format::JsonPretty => serde_json::to_writer_pretty(writer, &self.db)
-                .with_context(|| format!("failed serializing db json: {}", self.path.display()))?,
-            Format::Json => serde_json::to_writer(writer, &self.db)
-                .with_context(|| format!("failed serializing db json: {}", self.path.display()))?,
-            Format::Binary => bincode::serialize_into(writer, &self.db)
-                .with_context(|| format!("failed serializing db binary: {}", self.path.display()))?
+            let is_backup = file_name.to_str()
+                .and_then(|v| v.strip_prefix(&prefix))
+                .is_some_and(|ts| ts.starts_with(|c: char| c.is_ascii_digit()));
+
+            if is_backup {
+                existing.push(entry.path());
+            }
         }
 
-        log::info!("db save time: {:?}", start.elapsed());
+        let retention = self.config.backup_retention
+            .unwrap_or(BACKUP_RETENTION);
+
+        if existing.len() > retention {
+            existing.sort();
+
+            for old in &existing[..existing.len() - retention] {
+                log::info!("removing old backup {}", old.display());
+
+                std::fs::remove_file(old)
+                    .with_context(|| format!("failed to remove old backup: {}", old.display()))?;
+            }
+        }
 
         Ok(())
     }
 
     pub fn save(&self) -> anyhow::Result<()> {
-        self.write_file(false)
+        if self.readonly {
+            return Err(anyhow::anyhow!("db was loaded as read only"));
+        }
+
+        self.backup()?;
+        self.write_file(false)?;
+
+        if self.config.audit {
+            audit::record(&self.fsm_dir, self.loaded.as_ref(), &self.db);
+        }
+
+        Ok(())
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
     }
 
     pub fn path(&self) -> &Path {
@@ -372,7 +1024,7 @@ impl Context {
         path::RelativePath::from_root(&self.root, &path)
     }
 
-    pub fn rel_to_db_list<'a>(&self, path_list: &'a Vec<PathBuf>) -> path::RelativePathList<'a> {
+    pub fn rel_to_db_list<'a>(&self, path_list: &'a [PathBuf]) -> path::RelativePathList<'a> {
         path::RelativePathList::new(self.root.clone(), path_list)
     }
 }
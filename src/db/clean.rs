@@ -0,0 +1,46 @@
+use clap::Args;
+use anyhow::Context;
+
+use crate::db;
+use crate::prompt;
+
+#[derive(Debug, Args)]
+pub struct CleanArgs {
+    /// does not ask for confirmation before removing the files
+    #[arg(short, long)]
+    yes: bool,
+}
+
+pub fn clean_db(args: CleanArgs) -> anyhow::Result<()> {
+    let (path, _format) = db::Context::cwd_find()?;
+    let ignored = db::Context::find_ignored(&path)?;
+
+    if ignored.is_empty() {
+        println!("no unused db files found");
+
+        return Ok(());
+    }
+
+    println!("active db: {}", path.display());
+
+    for (found, _) in &ignored {
+        println!("unused db: {}", found.display());
+    }
+
+    if !args.yes && !prompt::confirm("remove the unused db files?")? {
+        println!("no files removed");
+
+        return Ok(());
+    }
+
+    for (found, _) in &ignored {
+        log::info!("removing {}", found.display());
+
+        std::fs::remove_file(found)
+            .with_context(|| format!("failed to remove db file: {}", found.display()))?;
+    }
+
+    println!("removed {} files", ignored.len());
+
+    Ok(())
+}
@@ -1,22 +1,107 @@
+use std::ffi::OsString;
+use std::path::Path;
+
 use clap::Args;
 use anyhow::Context;
 
+use crate::audit;
+use crate::config;
 use crate::db;
+use crate::fs;
+use crate::prompt;
 
 #[derive(Debug, Args)]
-pub struct DropArgs {}
+pub struct DropArgs {
+    /// does not ask for confirmation before dropping the db
+    #[arg(short, long)]
+    yes: bool,
+}
+
+/// finds any entries in the .fsm directory that will not be removed by the
+/// drop
+fn unknown_entries(dir: &Path, db_path: &Path, backup_dir: &Path, last_run: &Path) -> anyhow::Result<Vec<OsString>> {
+    let known = [
+        db_path.file_name(),
+        backup_dir.file_name(),
+        last_run.file_name(),
+        Some(config::CONFIG_NAME.as_ref()),
+        Some(audit::AUDIT_NAME.as_ref()),
+    ];
+    let mut rtn = Vec::new();
+
+    for entry in std::fs::read_dir(dir).context("failed to read .fsm directory")? {
+        let name = entry.context("failed to read .fsm directory entry")?
+            .file_name();
+
+        if !known.contains(&Some(name.as_os_str())) {
+            rtn.push(name);
+        }
+    }
+
+    Ok(rtn)
+}
 
-pub fn drop_db(_args: DropArgs) -> anyhow::Result<()> {
+pub fn drop_db(args: DropArgs) -> anyhow::Result<()> {
     let context = db::Context::cwd_load()?;
 
+    let backup_dir = context.backup_dir();
+    let last_run = context.last_run_path();
+    let dir = context.fsm_dir();
+
+    let unknown = unknown_entries(dir, context.path(), &backup_dir, &last_run)?;
+
+    if !unknown.is_empty() {
+        println!("{} contains files that are not managed by fsm:", dir.display());
+
+        for name in &unknown {
+            println!("{}", Path::new(name).display());
+        }
+
+        return Err(anyhow::anyhow!("remove or move the files before dropping the db"));
+    }
+
+    if !args.yes && !prompt::confirm(&format!("drop db and remove {}?", dir.display()))? {
+        println!("db not dropped");
+
+        return Ok(());
+    }
+
     log::info!("dropping db file: {}", context.path().display());
 
     std::fs::remove_file(context.path())
         .context("failed to remove db file")?;
 
-    let dir = context.path()
-        .parent()
-        .unwrap();
+    if fs::check_exists(&backup_dir).context("failed to check for backup directory")? {
+        log::info!("dropping backup directory: {}", backup_dir.display());
+
+        std::fs::remove_dir_all(&backup_dir)
+            .context("failed to remove backup directory")?;
+    }
+
+    if fs::check_exists(&last_run).context("failed to check for last run file")? {
+        log::info!("dropping last run file: {}", last_run.display());
+
+        std::fs::remove_file(&last_run)
+            .context("failed to remove last run file")?;
+    }
+
+    let config_path = dir.join(config::CONFIG_NAME);
+
+    if fs::check_exists(&config_path).context("failed to check for config file")? {
+        log::info!("dropping config file: {}", config_path.display());
+
+        std::fs::remove_file(&config_path)
+            .context("failed to remove config file")?;
+    }
+
+    let audit_path = dir.join(audit::AUDIT_NAME);
+
+    if fs::check_exists(&audit_path).context("failed to check for audit log")? {
+        log::info!("dropping audit log: {}", audit_path.display());
+
+        std::fs::remove_file(&audit_path)
+            .context("failed to remove audit log")?;
+    }
 
     log::info!("dropping fsm directory: {}", dir.display());
 
@@ -1,37 +1,276 @@
-use clap::Args;
+use std::fs::OpenOptions;
+use std::io::{BufReader, BufWriter, IsTerminal as _, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ArgGroup};
 use anyhow::Context;
+use globset::{GlobBuilder, GlobMatcher};
+use sha2::{Digest, Sha256};
 
+use crate::config::Config;
 use crate::db;
 
+/// dumps a database file
+///
+/// only one of --compact, --pretty, --debug, or --format can be given. if the
+/// db is stored as json and json output is selected then it will be streamed
+/// directly to the output without loading the entire db into memory
 #[derive(Debug, Args)]
+#[command(group(ArgGroup::new("repr")))]
 pub struct DumpArgs {
+    /// dumps the database as compact json
+    #[arg(long, group("repr"))]
+    compact: bool,
+
+    /// dumps the database as pretty printed json
+    #[arg(long, group("repr"))]
+    pretty: bool,
+
+    /// dumps the database with the rust debug representation
+    #[arg(long, group("repr"))]
+    debug: bool,
+
     /// dumps the database as json
-    #[arg(long)]
+    ///
+    /// same as --compact, or --pretty when both are given
+    #[arg(long, conflicts_with_all(["compact", "debug", "format"]))]
     json: bool,
 
-    /// pretty prints the output
+    /// writes the db in the specified format regardless of how it is stored
+    ///
+    /// defaults to the `dump_format` value in the db config if no other
+    /// representation is given
+    #[arg(long, value_enum, group("repr"))]
+    format: Option<db::Format>,
+
+    /// writes the output to a file instead of stdout, or stdout with -
     #[arg(long)]
-    pretty: bool,
+    out: Option<PathBuf>,
+
+    /// allows writing a binary format to stdout when it is a terminal
+    #[arg(long)]
+    force: bool,
+
+    /// only dumps files with a db entry that matches the glob
+    ///
+    /// e.g. `photos/**`. collections that contain a matching file are
+    /// included with only the matching files. the db tags and comment are
+    /// always included
+    #[arg(long, value_name("GLOB"), value_parser(parse_glob))]
+    only: Option<GlobMatcher>,
+
+    /// includes all files of collections that contain a matching file
+    #[arg(long, requires("only"))]
+    full_collections: bool,
+
+    /// prints a sha-256 checksum of the db contents instead of the db
+    ///
+    /// the checksum is the same for dbs with the same data regardless of the
+    /// format they are stored in
+    #[arg(long, conflicts_with_all(["repr", "json", "out", "force"]))]
+    checksum: bool,
 }
 
-pub fn dump_db(args: DumpArgs) -> anyhow::Result<()> {
-    let context = db::Context::cwd_load()?;
-
-    if args.json {
-        if args.pretty {
-            serde_json::to_writer_pretty(std::io::stdout(), &context.db)
-                .context("failed writing db to output")?;
-        } else {
-            serde_json::to_writer(std::io::stdout(), &context.db)
-                .context("failed writing db to output")?;
+/// parses a glob where `*` does not match across directories and `**` does
+fn parse_glob(given: &str) -> Result<GlobMatcher, globset::Error> {
+    let glob = GlobBuilder::new(given)
+        .literal_separator(true)
+        .build()?;
+
+    Ok(glob.compile_matcher())
+}
+
+/// removes files and collection members that do not match the glob
+fn project_db(db: &mut db::Db, matcher: &GlobMatcher, full_collections: bool) {
+    db.files.retain(|key, _| matcher.is_match(&**key));
+
+    db.collections.retain(|_, coll| coll.iter().any(|key| matcher.is_match(&**key)));
+
+    if !full_collections {
+        for coll in db.collections.values_mut() {
+            coll.retain(|key| matcher.is_match(&**key));
         }
+    }
+}
+
+/// transcodes the stored json db to the output one value at a time
+fn stream_json<W>(path: &Path, output: W, pretty: bool) -> anyhow::Result<()>
+where
+    W: Write
+{
+    log::info!("streaming {}", path.display());
+
+    let file = OpenOptions::new()
+        .read(true)
+        .open(path)
+        .with_context(|| format!("failed reading db: {}", path.display()))?;
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(file));
+
+    if pretty {
+        let mut serializer = serde_json::Serializer::pretty(output);
+
+        serde_transcode::transcode(&mut deserializer, &mut serializer)
+            .context("failed writing db to output")?;
+    } else {
+        let mut serializer = serde_json::Serializer::new(output);
+
+        serde_transcode::transcode(&mut deserializer, &mut serializer)
+            .context("failed writing db to output")?;
+    }
+
+    deserializer.end()
+        .with_context(|| format!("trailing data in db json: {}", path.display()))?;
+
+    Ok(())
+}
+
+/// writes the db in the given format, streaming stored json when possible
+fn dump_format<W>(path: &Path, stored: &db::Format, format: &db::Format, mut output: W) -> anyhow::Result<()>
+where
+    W: Write
+{
+    let is_json = matches!(format, db::Format::Json | db::Format::JsonPretty);
+
+    if is_json && matches!(stored, db::Format::Json | db::Format::JsonPretty) {
+        stream_json(path, &mut output, matches!(format, db::Format::JsonPretty))?;
+    } else {
+        let context = db::Context::load_from(path)?.readonly();
+
+        format.write_db(&mut output, &context.db)
+            .context("failed writing db to output")?;
+    }
+
+    if is_json {
+        writeln!(output).context("failed writing db to output")?;
+    }
+
+    Ok(())
+}
+
+fn dump_debug<W>(path: &Path, pretty: bool, mut output: W) -> anyhow::Result<()>
+where
+    W: Write
+{
+    let context = db::Context::load_from(path)?.readonly();
+
+    if pretty {
+        write!(output, "{:#?}", context.db)
+            .context("failed writing db to output")?;
     } else {
-        if args.pretty {
-            println!("{:#?}", context.db);
-        } else {
-            println!("{:?}", context.db);
+        write!(output, "{:?}", context.db)
+            .context("failed writing db to output")?;
+    }
+
+    writeln!(output).context("failed writing db to output")?;
+
+    Ok(())
+}
+
+/// determines the format to write based on the given flags and the db config
+fn selected_format(path: &Path, args: &DumpArgs) -> Option<db::Format> {
+    if args.pretty {
+        Some(db::Format::JsonPretty)
+    } else if args.compact || args.json {
+        Some(db::Format::Json)
+    } else if args.format.is_some() {
+        args.format.clone()
+    } else if !args.debug {
+        path.parent()
+            .map(Config::load)
+            .and_then(|config| config.dump_format)
+    } else {
+        None
+    }
+}
+
+/// writes only the matching portion of the db
+fn dump_projected<W>(path: &Path, args: &DumpArgs, matcher: &GlobMatcher, selected: Option<db::Format>, mut output: W) -> anyhow::Result<()>
+where
+    W: Write
+{
+    let mut context = db::Context::load_from(path)?.readonly();
+
+    project_db(&mut context.db, matcher, args.full_collections);
+
+    if let Some(format) = selected {
+        format.write_db(&mut output, &context.db)
+            .context("failed writing db to output")?;
+
+        if matches!(format, db::Format::Json | db::Format::JsonPretty) {
+            writeln!(output).context("failed writing db to output")?;
         }
+    } else if args.debug {
+        writeln!(output, "{:#?}", context.db).context("failed writing db to output")?;
+    } else {
+        writeln!(output, "{:?}", context.db).context("failed writing db to output")?;
     }
 
     Ok(())
 }
+
+/// prints the checksum of the db serialized as compact json
+///
+/// all maps in the db are ordered so the same data always serializes to the
+/// same bytes
+fn dump_checksum(path: &Path, args: &DumpArgs) -> anyhow::Result<()> {
+    let mut context = db::Context::load_from(path)?.readonly();
+
+    if let Some(matcher) = &args.only {
+        project_db(&mut context.db, matcher, args.full_collections);
+    }
+
+    let mut hasher = Sha256::new();
+
+    serde_json::to_writer(&mut hasher, &context.db)
+        .context("failed serializing db for checksum")?;
+
+    println!("{:x}", hasher.finalize());
+
+    Ok(())
+}
+
+fn dump_to<W>(path: &Path, stored: &db::Format, selected: Option<db::Format>, args: &DumpArgs, mut output: W) -> anyhow::Result<()>
+where
+    W: Write
+{
+    if let Some(matcher) = &args.only {
+        dump_projected(path, args, matcher, selected, &mut output)?;
+    } else if let Some(format) = selected {
+        dump_format(path, stored, &format, &mut output)?;
+    } else {
+        dump_debug(path, args.debug, &mut output)?;
+    }
+
+    output.flush().context("failed writing db to output")?;
+
+    Ok(())
+}
+
+pub fn dump_db(args: DumpArgs) -> anyhow::Result<()> {
+    let (path, stored) = db::Context::cwd_find()?;
+
+    if args.checksum {
+        return dump_checksum(&path, &args);
+    }
+
+    let selected = selected_format(&path, &args);
+
+    if let Some(out) = args.out.as_ref().filter(|out| out.as_os_str() != "-") {
+        let file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(out)
+            .with_context(|| format!("failed to open output file: {}", out.display()))?;
+
+        dump_to(&path, &stored, selected, &args, BufWriter::new(file))
+    } else {
+        let is_binary = matches!(selected, Some(db::Format::Binary | db::Format::BinaryGz));
+
+        if is_binary && !args.force && std::io::stdout().is_terminal() {
+            return Err(anyhow::anyhow!("refusing to write binary data to a terminal. use --out or --force"));
+        }
+
+        dump_to(&path, &stored, selected, &args, BufWriter::new(std::io::stdout().lock()))
+    }
+}
@@ -0,0 +1,41 @@
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::Args;
+use anyhow::Context;
+
+use crate::db;
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// the format to write the db as
+    #[arg(long, value_enum, default_value("json"))]
+    format: db::Format,
+
+    /// the file to write the db to
+    #[arg(long)]
+    out: PathBuf,
+}
+
+pub fn export_db(args: ExportArgs) -> anyhow::Result<()> {
+    let context = db::Context::load_readonly()?;
+
+    let file = OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .create(true)
+        .open(&args.out)
+        .with_context(|| format!("failed to open export file: {}", args.out.display()))?;
+    let mut writer = BufWriter::new(file);
+
+    args.format.write_db(&mut writer, &context.db)
+        .with_context(|| format!("failed exporting db: {}", args.out.display()))?;
+
+    writer.flush()
+        .with_context(|| format!("failed writing export file: {}", args.out.display()))?;
+
+    println!("exported {} files to {}", context.db.files.len(), args.out.display());
+
+    Ok(())
+}
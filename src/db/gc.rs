@@ -0,0 +1,86 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use clap::Args;
+
+use crate::db;
+use crate::fs;
+use crate::path;
+
+#[derive(Debug, Args)]
+pub struct GcArgs {
+    /// reports what would be removed without saving any changes
+    #[arg(long)]
+    dry_run: bool,
+}
+
+pub fn gc_db(args: GcArgs) -> anyhow::Result<()> {
+    let mut context = db::Context::cwd_load()?;
+    let root = context.root_copy();
+
+    // cache of the existence checks since collections will commonly reference
+    // the same files as the file entries
+    let mut checked: BTreeMap<Box<str>, bool> = BTreeMap::new();
+    let mut check = |key: &str| -> anyhow::Result<bool> {
+        if let Some(exists) = checked.get(key) {
+            return Ok(*exists);
+        }
+
+        let exists = fs::check_exists(&path::entry_to_path(&root, key))?;
+
+        checked.insert(key.into(), exists);
+
+        Ok(exists)
+    };
+
+    let mut removed_files = 0usize;
+    let mut removed_refs = 0usize;
+    let mut updated = BTreeMap::new();
+
+    for (file, data) in std::mem::take(&mut context.db.files) {
+        if check(&file)? {
+            updated.insert(file, data);
+        } else {
+            if args.dry_run {
+                println!("would remove file: {}", file);
+            } else {
+                log::info!("removing file: {}", file);
+            }
+
+            removed_files += 1;
+        }
+    }
+
+    context.db.files = updated;
+
+    for (name, coll) in context.db.collections.iter_mut() {
+        let mut kept = BTreeSet::new();
+
+        for file in std::mem::take(coll) {
+            if check(&file)? {
+                kept.insert(file);
+            } else {
+                if args.dry_run {
+                    println!("would remove {} from collection: {}", file, name);
+                } else {
+                    log::info!("removing {} from collection: {}", file, name);
+                }
+
+                removed_refs += 1;
+            }
+        }
+
+        *coll = kept;
+    }
+
+    if args.dry_run {
+        println!("would remove {removed_files} file entries and {removed_refs} collection references");
+    } else {
+        if removed_files > 0 || removed_refs > 0 {
+            context.save()?;
+        }
+
+        println!("removed {removed_files} file entries and {removed_refs} collection references");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use clap::Args;
+
+use crate::db::{self, MetaContainer};
+use crate::time;
+
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    /// only lists entries modified after the given date
+    ///
+    /// accepts relative values like 3h, 7d, or 2w as well as RFC3339 or
+    /// YYYY-MM-DD dates. entries that have never been updated use their
+    /// created date
+    #[arg(long, default_value("7d"), value_parser(time::parse_date_filter))]
+    since: time::DateTime,
+
+    /// the max number of entries to display
+    #[arg(long)]
+    limit: Option<usize>,
+}
+
+pub fn history_db(args: HistoryArgs) -> anyhow::Result<()> {
+    let context = db::Context::load_readonly()?;
+
+    let mut changed: Vec<(&str, &dyn MetaContainer)> = Vec::new();
+
+    if *context.db.modified() >= args.since {
+        changed.push(("!SELF", &context.db));
+    }
+
+    for (key, file) in &context.db.files {
+        if *file.modified() >= args.since {
+            changed.push((key, file));
+        }
+    }
+
+    // most recent first, stable so entries with the same timestamp keep the
+    // order they were found in
+    changed.sort_by(|(_, a), (_, b)| b.modified().cmp(a.modified()));
+
+    let total = changed.len();
+    let local_offset = chrono::Local;
+
+    for (key, data) in changed.into_iter().take(args.limit.unwrap_or(usize::MAX)) {
+        let status = if data.updated().is_some() {
+            "updated"
+        } else {
+            "created"
+        };
+
+        println!("{} {} {}", data.modified().with_timezone(&local_offset), status, key);
+    }
+
+    println!("Total: {total}");
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::fs::OpenOptions;
+use std::io::{BufReader, Read};
+use std::path::PathBuf;
+
+use clap::{Args, ArgGroup};
+use anyhow::Context;
+
+use crate::db::{self, MetaContainer as _};
+use crate::db::merge::{merge_into, Strategy};
+
+/// loads a db that was written by export into the current db
+///
+/// by default the imported data is merged with the current db keeping the
+/// current data when both contain the same entry
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("strategy")))]
+pub struct ImportArgs {
+    /// the format of the file
+    ///
+    /// if not specified then the format will be determined from the file name
+    #[arg(long, value_enum)]
+    format: Option<db::Format>,
+
+    /// replaces the current db with the imported data
+    #[arg(long, group("strategy"))]
+    replace: bool,
+
+    /// uses the imported data when both dbs contain the same entry
+    #[arg(long, group("strategy"))]
+    prefer_theirs: bool,
+
+    /// uses the most recently modified data when both dbs contain the same
+    /// entry
+    #[arg(long, group("strategy"))]
+    newer: bool,
+
+    /// the file to read the db from, or stdin with -
+    ///
+    /// stdin is read as binary unless --format is given
+    #[arg(long = "in")]
+    in_: PathBuf,
+}
+
+pub fn import_db(args: ImportArgs) -> anyhow::Result<()> {
+    let mut context = db::Context::cwd_load()?;
+
+    let format = args.format.unwrap_or_else(|| db::Format::from_path(&args.in_));
+
+    let reader: Box<dyn Read> = if args.in_.as_os_str() == "-" {
+        Box::new(std::io::stdin().lock())
+    } else {
+        let file = OpenOptions::new()
+            .read(true)
+            .open(&args.in_)
+            .with_context(|| format!("failed reading import file: {}", args.in_.display()))?;
+
+        Box::new(file)
+    };
+
+    let imported = format.read_db(BufReader::new(reader))
+        .with_context(|| format!("failed importing db: {}", args.in_.display()))?;
+
+    if args.replace {
+        let total = imported.files.len();
+
+        context.db = imported;
+        context.db.update_ts();
+        context.save()?;
+
+        println!("replaced db with {} files", total);
+
+        return Ok(());
+    }
+
+    let strategy = if args.prefer_theirs {
+        Strategy::Theirs
+    } else if args.newer {
+        Strategy::Newer
+    } else {
+        Strategy::Ours
+    };
+
+    let counts = merge_into(&mut context.db, imported, strategy);
+
+    context.save()?;
+
+    println!(
+        "added {} files, replaced {} files, kept {} files, merged {} collections",
+        counts.added,
+        counts.replaced,
+        counts.kept,
+        counts.collections,
+    );
+
+    Ok(())
+}
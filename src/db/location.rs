@@ -0,0 +1,25 @@
+use clap::{Args, ValueEnum};
+
+use crate::db;
+
+#[derive(Debug, Args)]
+pub struct PathArgs {}
+
+pub fn path_db(_args: PathArgs) -> anyhow::Result<()> {
+    let (path, format) = db::Context::cwd_find()?;
+    let root = db::Context::get_root(&path)?;
+
+    let format_name = format.to_possible_value()
+        .map(|value| value.get_name().to_owned())
+        .unwrap_or_default();
+
+    println!("path: {}", path.display());
+    println!("format: {}", format_name);
+    println!("root: {}", root.display());
+
+    for (ignored, _) in db::Context::find_ignored(&path)? {
+        println!("warning: ignored db file: {}", ignored.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use std::collections::btree_map::Entry;
+use std::path::PathBuf;
+
+use clap::{Args, ArgGroup};
+
+use crate::db::{self, Db, MetaContainer};
+use crate::tags;
+
+/// merges another db into the current db
+///
+/// file paths are stored relative to the root of each db so merging assumes
+/// that both dbs have compatible roots. entries for the same file in both dbs
+/// are resolved as a whole by the chosen strategy, collections with the same
+/// name are combined.
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("strategy")))]
+pub struct MergeArgs {
+    /// keeps the current data when both dbs contain the same entry
+    ///
+    /// this is the default strategy
+    #[arg(long, group("strategy"))]
+    prefer_ours: bool,
+
+    /// uses the other data when both dbs contain the same entry
+    #[arg(long, group("strategy"))]
+    prefer_theirs: bool,
+
+    /// uses the most recently modified data when both dbs contain the same
+    /// entry
+    #[arg(long, group("strategy"))]
+    newer: bool,
+
+    /// the path to the other db
+    ///
+    /// can be a db file, a .fsm directory, or a directory containing a .fsm
+    /// directory
+    other: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Strategy {
+    Ours,
+    Theirs,
+    Newer,
+}
+
+impl Strategy {
+    fn prefer_theirs<M>(&self, ours: &M, theirs: &M) -> bool
+    where
+        M: MetaContainer
+    {
+        match self {
+            Strategy::Ours => false,
+            Strategy::Theirs => true,
+            Strategy::Newer => theirs.modified() > ours.modified(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MergeCounts {
+    pub added: usize,
+    pub replaced: usize,
+    pub kept: usize,
+    pub collections: usize,
+}
+
+fn merge_tags(ours: &mut tags::TagsMap, theirs: tags::TagsMap, use_theirs: bool) {
+    for (key, value) in theirs {
+        if use_theirs || !ours.contains_key(&key) {
+            ours.insert(key, value);
+        }
+    }
+}
+
+/// merges the other db into the current one using the given strategy
+pub fn merge_into(ours: &mut Db, mut theirs: Db, strategy: Strategy) -> MergeCounts {
+    let mut counts = MergeCounts::default();
+    let use_theirs = strategy.prefer_theirs(&*ours, &theirs);
+
+    merge_tags(&mut ours.tags, theirs.take_tags(), use_theirs);
+
+    if let Some(comment) = theirs.take_comment() {
+        if use_theirs || ours.comment.is_none() {
+            ours.comment = Some(comment);
+        }
+    }
+
+    for (key, file) in std::mem::take(&mut theirs.files) {
+        match ours.files.entry(key) {
+            Entry::Vacant(vacant) => {
+                log::info!("adding {}", vacant.key());
+
+                vacant.insert(file);
+                counts.added += 1;
+            }
+            Entry::Occupied(mut occupied) => {
+                if strategy.prefer_theirs(occupied.get(), &file) {
+                    log::info!("replacing {}", occupied.key());
+
+                    occupied.insert(file);
+                    counts.replaced += 1;
+                } else {
+                    log::info!("keeping {}", occupied.key());
+
+                    counts.kept += 1;
+                }
+            }
+        }
+    }
+
+    for (name, files) in std::mem::take(&mut theirs.collections) {
+        log::info!("merging collection {}", name);
+
+        ours.collections.entry(name)
+            .or_default()
+            .extend(files);
+
+        counts.collections += 1;
+    }
+
+    ours.update_ts();
+
+    counts
+}
+
+pub fn merge_db(args: MergeArgs) -> anyhow::Result<()> {
+    let mut context = db::Context::cwd_load()?;
+    let other = db::Context::load_from(&args.other)?;
+
+    if other.path() == context.path() {
+        return Err(anyhow::anyhow!("cannot merge a db into itself"));
+    }
+
+    let strategy = if args.prefer_theirs {
+        Strategy::Theirs
+    } else if args.newer {
+        Strategy::Newer
+    } else {
+        Strategy::Ours
+    };
+
+    let counts = merge_into(&mut context.db, other.db, strategy);
+
+    context.save()?;
+
+    println!(
+        "added {} files, replaced {} files, kept {} files, merged {} collections",
+        counts.added,
+        counts.replaced,
+        counts.kept,
+        counts.collections,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use clap::Args;
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::db;
+use crate::tags;
+use crate::time;
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// outputs the stats as json
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Stats<'a> {
+    files: usize,
+    tag_keys: usize,
+    tag_types: BTreeMap<&'static str, usize>,
+    comments: usize,
+    collections: usize,
+    collection_members: usize,
+    created: &'a time::DateTime,
+    updated: Option<&'a time::DateTime>,
+}
+
+fn count_tags<'a>(map: &'a tags::TagsMap, keys: &mut BTreeSet<&'a str>, stats: &mut Stats) {
+    for (key, value) in map {
+        let type_name = value.as_ref()
+            .map(tags::TagValue::type_name)
+            .unwrap_or("none");
+
+        keys.insert(key);
+
+        *stats.tag_types.entry(type_name).or_default() += 1;
+    }
+}
+
+pub fn stats_db(args: StatsArgs) -> anyhow::Result<()> {
+    let context = db::Context::load_readonly()?;
+
+    let mut stats = Stats {
+        files: context.db.files.len(),
+        tag_keys: 0,
+        tag_types: BTreeMap::new(),
+        comments: 0,
+        collections: context.db.collections.len(),
+        collection_members: 0,
+        created: &context.db.created,
+        updated: context.db.updated.as_ref(),
+    };
+    let mut keys = BTreeSet::new();
+
+    count_tags(&context.db.tags, &mut keys, &mut stats);
+
+    for file in context.db.files.values() {
+        count_tags(&file.tags, &mut keys, &mut stats);
+
+        if file.comment.is_some() {
+            stats.comments += 1;
+        }
+    }
+
+    stats.tag_keys = keys.len();
+    stats.collection_members = context.db.collections.values()
+        .map(|coll| coll.len())
+        .sum();
+
+    if args.json {
+        serde_json::to_writer(std::io::stdout(), &stats)
+            .context("failed writing stats to output")?;
+
+        println!();
+
+        return Ok(());
+    }
+
+    let local_offset = chrono::Local;
+
+    println!("files: {}", stats.files);
+    println!("tag keys: {}", stats.tag_keys);
+
+    for (type_name, count) in &stats.tag_types {
+        println!("    {}: {}", type_name, count);
+    }
+
+    println!("comments: {}", stats.comments);
+    println!("collections: {}", stats.collections);
+    println!("collection members: {}", stats.collection_members);
+    println!("created: {}", stats.created.with_timezone(&local_offset));
+
+    if let Some(updated) = stats.updated {
+        println!("updated: {}", updated.with_timezone(&local_offset));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use clap::Args;
+
+use crate::db;
+use crate::fs;
+use crate::path;
+use crate::tags;
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// removes collection references to files that are not in the db
+    #[arg(long)]
+    fix: bool,
+}
+
+fn verify_path_value(entry: &str, key: &str, value: &tags::TagValue, root: &Path) -> anyhow::Result<usize> {
+    match value {
+        tags::TagValue::Path(path_entry) => {
+            let full = path::entry_to_path(root, path_entry);
+
+            if fs::check_exists(&full)? {
+                Ok(0)
+            } else {
+                println!("{}: path tag \"{}\" does not exist \"{}\"", entry, key, path_entry);
+
+                Ok(1)
+            }
+        }
+        tags::TagValue::List(list) => {
+            let mut problems = 0;
+
+            for item in list {
+                problems += verify_path_value(entry, key, item, root)?;
+            }
+
+            Ok(problems)
+        }
+        _ => Ok(0),
+    }
+}
+
+fn verify_tags(entry: &str, map: &tags::TagsMap, root: &Path) -> anyhow::Result<usize> {
+    let mut problems = 0;
+
+    for (key, value) in map {
+        if key.parse::<tags::TagKey>().is_err() {
+            println!("{}: invalid tag key \"{}\"", entry, key);
+
+            problems += 1;
+        }
+
+        if let Some(value) = value {
+            problems += verify_path_value(entry, key, value, root)?;
+        }
+    }
+
+    Ok(problems)
+}
+
+pub fn verify_db(args: VerifyArgs) -> anyhow::Result<()> {
+    let mut context = db::Context::cwd_load()?;
+
+    let mut problems = 0usize;
+    let mut fixed = 0usize;
+
+    for (ignored, _) in db::Context::find_ignored(context.path())? {
+        println!("{}: db file is ignored, only {} is used", ignored.display(), context.path().display());
+
+        problems += 1;
+    }
+
+    problems += verify_tags("!SELF", &context.db.tags, context.root())?;
+
+    for (key, file) in &context.db.files {
+        if key.contains('\\') {
+            println!("{}: entry is not normalized to forward slashes", key);
+
+            problems += 1;
+        }
+
+        problems += verify_tags(key, &file.tags, context.root())?;
+    }
+
+    for (name, coll) in context.db.collections.iter_mut() {
+        let mut dangling = Vec::new();
+
+        for file in coll.iter() {
+            if !context.db.files.contains_key(file) {
+                dangling.push(file.clone());
+            }
+        }
+
+        for file in dangling {
+            if args.fix {
+                println!("{}: removed \"{}\", not found in db", name, file);
+
+                coll.remove(&file);
+
+                fixed += 1;
+            } else {
+                println!("{}: \"{}\" not found in db", name, file);
+
+                problems += 1;
+            }
+        }
+    }
+
+    if fixed > 0 {
+        context.save()?;
+
+        println!("fixed {fixed} problems");
+    }
+
+    if problems > 0 {
+        return Err(anyhow::anyhow!("found {problems} problems in db"));
+    }
+
+    println!("no problems found");
+
+    Ok(())
+}
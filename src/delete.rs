@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 use clap::Args;
@@ -6,6 +6,7 @@ use clap::Args;
 use crate::logging;
 use crate::fs;
 use crate::db;
+use crate::path;
 
 #[derive(Debug, Args)]
 pub struct DeleteArgs {
@@ -13,7 +14,14 @@ pub struct DeleteArgs {
     #[arg(long)]
     not_exists: bool,
 
+    /// displays the entries that would be removed without saving
+    #[arg(long)]
+    dry_run: bool,
+
     /// the file(s) to remove from the database
+    ///
+    /// an argument of @<file> will be replaced by the newline separated paths
+    /// in that file
     #[arg(
         trailing_var_arg = true,
         required_unless_present("not_exists")
@@ -21,14 +29,18 @@ pub struct DeleteArgs {
     files: Vec<PathBuf>,
 }
 
-pub fn delete_data(args: DeleteArgs) -> anyhow::Result<()> {
+pub fn delete_data(mut args: DeleteArgs) -> anyhow::Result<()> {
     let mut context = db::Context::cwd_load()?;
+
+    args.files = path::expand_arg_files(std::mem::take(&mut args.files))?;
+
     let root = context.root_copy();
+    let mut removed = 0usize;
 
     if args.not_exists {
         let mut updated = BTreeMap::new();
 
-        for (file, data) in context.db.files {
+        for (file, data) in std::mem::take(&mut context.db.files) {
             let full_path = root.join(&*file);
 
             if fs::check_exists(&full_path)? {
@@ -36,13 +48,21 @@ pub fn delete_data(args: DeleteArgs) -> anyhow::Result<()> {
 
                 updated.insert(file, data);
             } else {
-                log::info!("removing {}", file);
+                if args.dry_run {
+                    println!("would remove: {}", file);
+                } else {
+                    log::info!("removing {}", file);
+                }
+
+                removed += 1;
             }
         }
 
         context.db.files = updated;
     }
 
+    let mut seen = BTreeSet::new();
+
     for path_result in context.rel_to_db_list(&args.files) {
         let Some(rel_path) = logging::log_result(path_result) else {
             continue;
@@ -50,16 +70,35 @@ pub fn delete_data(args: DeleteArgs) -> anyhow::Result<()> {
 
         let (_path, db_entry) = rel_path.into();
 
+        if !seen.insert(db_entry.clone()) {
+            log::info!("skipping duplicate entry: {}", db_entry);
+            continue;
+        }
+
         log::info!("looking for: {}", db_entry);
 
         if let Some(_removed) = context.db.files.remove(&db_entry) {
-            log::info!("file not found in db: {}", db_entry);
+            if args.dry_run {
+                println!("would remove: {}", db_entry);
+            } else {
+                log::info!("file removed from db: {}", db_entry);
+            }
+
+            removed += 1;
         } else {
-            log::info!("file removed from db: {}", db_entry);
+            log::info!("file not found in db: {}", db_entry);
         }
     }
 
+    if args.dry_run {
+        println!("would remove {removed} entries");
+
+        return Ok(());
+    }
+
     context.save()?;
 
+    println!("removed {removed} entries");
+
     Ok(())
 }
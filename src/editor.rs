@@ -0,0 +1,75 @@
+use std::io::Write as _;
+use std::process::Command;
+
+use anyhow::Context;
+
+const VISUAL_ENV: &str = "VISUAL";
+const EDITOR_ENV: &str = "EDITOR";
+
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+fn get_editor() -> String {
+    for name in [VISUAL_ENV, EDITOR_ENV] {
+        if let Ok(value) = std::env::var(name) {
+            if !value.trim().is_empty() {
+                return value;
+            }
+        }
+    }
+
+    DEFAULT_EDITOR.to_owned()
+}
+
+/// opens the given text in the users editor and returns the saved result
+///
+/// the editor is retrieved from `$VISUAL` then `$EDITOR` before falling back
+/// to a system default. if the editor exits unsuccessfully then `None` is
+/// returned
+///
+/// the text is written to a newly created temp file with a random name that
+/// is removed when editing is done
+pub fn edit(initial: &str) -> anyhow::Result<Option<String>> {
+    let mut tmp_file = tempfile::Builder::new()
+        .prefix("fsm-edit-")
+        .suffix(".txt")
+        .tempfile()
+        .context("failed to create temp file")?;
+    let tmp_path = tmp_file.path().to_path_buf();
+
+    tmp_file.write_all(initial.as_bytes())
+        .and_then(|_| tmp_file.flush())
+        .with_context(|| format!("failed to write temp file: {}", tmp_path.display()))?;
+
+    let editor = get_editor();
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or(DEFAULT_EDITOR);
+
+    log::info!("opening editor: {}", editor);
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(&tmp_path)
+        .status()
+        .with_context(|| format!("failed to run editor: {}", editor));
+
+    let result = match status {
+        Ok(status) if status.success() => std::fs::read_to_string(&tmp_path)
+            .with_context(|| format!("failed to read temp file: {}", tmp_path.display()))
+            .map(Some),
+        Ok(status) => {
+            log::info!("editor exited with {}", status);
+
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    };
+
+    if let Err(err) = tmp_file.close() {
+        log::info!("failed to remove temp file: {}", err);
+    }
+
+    result
+}
@@ -0,0 +1,47 @@
+use std::sync::OnceLock;
+
+/// exit code used when a requested item could not be found
+pub const EXIT_NOT_FOUND: u8 = 3;
+
+static STRICT: OnceLock<bool> = OnceLock::new();
+
+/// enables strict mode where missing items will cause a non-zero exit code
+pub fn set_strict(strict: bool) {
+    let _ = STRICT.set(strict);
+}
+
+pub fn is_strict() -> bool {
+    STRICT.get().copied().unwrap_or(false)
+}
+
+/// an error that has already been displayed to the user
+#[derive(Debug, thiserror::Error)]
+pub enum Reported {
+    #[error("{0}")]
+    NotFound(String),
+}
+
+impl Reported {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Reported::NotFound(_) => EXIT_NOT_FOUND,
+        }
+    }
+}
+
+/// displays the message and returns a not found error if strict mode is
+/// enabled
+pub fn not_found<M>(msg: M) -> anyhow::Result<()>
+where
+    M: Into<String>
+{
+    let msg = msg.into();
+
+    println!("{msg}");
+
+    if is_strict() {
+        Err(Reported::NotFound(msg).into())
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,332 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+use clap::Args;
+
+use crate::tags;
+use crate::time;
+use crate::db::MetaContainer;
+
+/// a tag key with an optional value to match against
+///
+/// specified as `key` or `key:value`. if a value is given then the tag must
+/// have a matching value, or for list tags contain a matching value
+#[derive(Debug, Clone)]
+pub struct TagFilter {
+    key: tags::TagKey,
+    value: Option<String>,
+}
+
+impl TagFilter {
+    /// checks if the given tags map matches the filter
+    pub fn matches(&self, tags: &tags::TagsMap) -> bool {
+        let Some(found) = tags.get(self.key.inner()) else {
+            return false;
+        };
+
+        let Some(value) = &self.value else {
+            return true;
+        };
+
+        found.as_ref().is_some_and(|v| v.matches(value))
+    }
+}
+
+impl Display for TagFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if let Some(value) = &self.value {
+            write!(f, "{}:{}", self.key, value)
+        } else {
+            write!(f, "{}", self.key)
+        }
+    }
+}
+
+impl FromStr for TagFilter {
+    type Err = tags::InvalidTagChars;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some((key, value)) = value.split_once(':') {
+            Ok(TagFilter {
+                key: key.parse()?,
+                value: Some(value.to_owned()),
+            })
+        } else {
+            Ok(TagFilter {
+                key: value.parse()?,
+                value: None,
+            })
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct FilterArgs {
+    /// filters out results that do not contain the desired tags
+    ///
+    /// this will be considered a AND operation with exclude tags, so a given
+    /// record must fulfill both include and exclude rules. by default a
+    /// record must contain all of the include tags, see --match-any. a value
+    /// can be specified with `key:value` and list tags will match if they
+    /// contain the value
+    #[arg(long, value_delimiter(','))]
+    pub includes_tags: Vec<TagFilter>,
+
+    /// a record only needs to contain one of the include tags
+    ///
+    /// exclude tags are not affected, a record that contains any of the
+    /// exclude tags is still filtered out. e.g. `--includes-tags a,b
+    /// --excludes-tags c --match-any` is (a OR b) AND NOT c
+    #[arg(long, requires("includes_tags"))]
+    pub match_any: bool,
+
+    /// filters out results that do contain the desired tags
+    ///
+    /// this will be considered a AND operation with include tags, so a given
+    /// record must fulfill both include and exclude rules. a value can be
+    /// specified with `key:value` and list tags will match if they contain
+    /// the value
+    #[arg(long, value_delimiter(','))]
+    pub excludes_tags: Vec<TagFilter>,
+
+    /// filters out results that were last modified before the given date
+    ///
+    /// accepts relative values like 3h, 7d, or 2w as well as RFC3339 or
+    /// YYYY-MM-DD dates. entries that have never been updated use their
+    /// created date
+    #[arg(long, value_parser(time::parse_date_filter))]
+    pub updated_after: Option<time::DateTime>,
+
+    /// filters out results that were last modified after the given date
+    ///
+    /// accepts the same values as --updated-after
+    #[arg(long, value_parser(time::parse_date_filter))]
+    pub updated_before: Option<time::DateTime>,
+
+    /// filters out results that were created before the given date
+    ///
+    /// accepts the same values as --updated-after
+    #[arg(long, value_parser(time::parse_date_filter))]
+    pub created_after: Option<time::DateTime>,
+
+    /// filters out results that were created after the given date
+    ///
+    /// accepts the same values as --updated-after
+    #[arg(long, value_parser(time::parse_date_filter))]
+    pub created_before: Option<time::DateTime>,
+
+    /// only keeps results that have not been modified within the given
+    /// duration
+    ///
+    /// accepts relative values like 3h, 7d, or 2w
+    #[arg(long, visible_alias("older-than"), value_name("DURATION"), value_parser(time::parse_age))]
+    pub stale: Option<time::DateTime>,
+
+    /// only keeps results that have been modified within the given duration
+    ///
+    /// accepts relative values like 3h, 7d, or 2w
+    #[arg(long, visible_alias("newer-than"), value_name("DURATION"), value_parser(time::parse_age))]
+    pub fresh: Option<time::DateTime>,
+}
+
+impl FilterArgs {
+    /// checks if any filter rules were given
+    pub fn is_set(&self) -> bool {
+        !self.includes_tags.is_empty() ||
+            !self.excludes_tags.is_empty() ||
+            self.updated_after.is_some() ||
+            self.updated_before.is_some() ||
+            self.created_after.is_some() ||
+            self.created_before.is_some() ||
+            self.stale.is_some() ||
+            self.fresh.is_some()
+    }
+
+    /// each rule that was given in the order they are checked
+    fn rules(&self) -> impl Iterator<Item = Rule<'_>> {
+        let any = (self.match_any && !self.includes_tags.is_empty())
+            .then_some(Rule::IncludesAny(&self.includes_tags));
+        let all = self.includes_tags.iter()
+            .filter(|_| !self.match_any)
+            .map(Rule::Includes);
+
+        any.into_iter()
+            .chain(all)
+            .chain(self.excludes_tags.iter().map(Rule::Excludes))
+            .chain(self.updated_after.map(Rule::UpdatedAfter))
+            .chain(self.updated_before.map(Rule::UpdatedBefore))
+            .chain(self.created_after.map(Rule::CreatedAfter))
+            .chain(self.created_before.map(Rule::CreatedBefore))
+            .chain(self.stale.map(Rule::Stale))
+            .chain(self.fresh.map(Rule::Fresh))
+    }
+
+    /// checks if the given container passes all of the filter rules
+    pub fn check<M>(&self, meta: &M) -> bool
+    where
+        M: MetaContainer + ?Sized
+    {
+        self.rules().all(|rule| rule.passes(meta))
+    }
+
+    /// checks each filter rule against the given container and reports if
+    /// it passed
+    ///
+    /// the result of [`FilterArgs::check`] is the same as all rules passing
+    pub fn explain<M>(&self, meta: &M) -> Vec<RuleResult>
+    where
+        M: MetaContainer + ?Sized
+    {
+        self.rules()
+            .map(|rule| RuleResult {
+                rule: rule.describe(meta),
+                passed: rule.passes(meta),
+            })
+            .collect()
+    }
+}
+
+/// a single filter rule
+enum Rule<'a> {
+    Includes(&'a TagFilter),
+    IncludesAny(&'a [TagFilter]),
+    Excludes(&'a TagFilter),
+    UpdatedAfter(time::DateTime),
+    UpdatedBefore(time::DateTime),
+    CreatedAfter(time::DateTime),
+    CreatedBefore(time::DateTime),
+    Stale(time::DateTime),
+    Fresh(time::DateTime),
+}
+
+impl Rule<'_> {
+    fn passes<M>(&self, meta: &M) -> bool
+    where
+        M: MetaContainer + ?Sized
+    {
+        match self {
+            Rule::Includes(check) => check.matches(meta.tags()),
+            Rule::IncludesAny(list) => list.iter().any(|check| check.matches(meta.tags())),
+            Rule::Excludes(check) => !check.matches(meta.tags()),
+            Rule::UpdatedAfter(after) => meta.modified() >= after,
+            Rule::UpdatedBefore(before) => meta.modified() <= before,
+            Rule::CreatedAfter(after) => meta.created() >= after,
+            Rule::CreatedBefore(before) => meta.created() <= before,
+            Rule::Stale(cutoff) => meta.modified() < cutoff,
+            Rule::Fresh(cutoff) => meta.modified() >= cutoff,
+        }
+    }
+
+    fn describe<M>(&self, meta: &M) -> String
+    where
+        M: MetaContainer + ?Sized
+    {
+        match self {
+            Rule::Includes(check) => format!("includes tag {check}"),
+            Rule::IncludesAny(list) => {
+                let list: Vec<String> = list.iter()
+                    .map(|check| if check.matches(meta.tags()) {
+                        format!("{check} (found)")
+                    } else {
+                        format!("{check} (missing)")
+                    })
+                    .collect();
+
+                format!("includes any tag of {}", list.join(", "))
+            }
+            Rule::Excludes(check) => format!("excludes tag {check}"),
+            Rule::UpdatedAfter(after) => format!("updated after {after} (modified {})", meta.modified()),
+            Rule::UpdatedBefore(before) => format!("updated before {before} (modified {})", meta.modified()),
+            Rule::CreatedAfter(after) => format!("created after {after} (created {})", meta.created()),
+            Rule::CreatedBefore(before) => format!("created before {before} (created {})", meta.created()),
+            Rule::Stale(cutoff) => format!("stale, modified before {cutoff} (modified {})", meta.modified()),
+            Rule::Fresh(cutoff) => format!("fresh, modified since {cutoff} (modified {})", meta.modified()),
+        }
+    }
+}
+
+/// the outcome of a single filter rule
+#[derive(Debug)]
+pub struct RuleResult {
+    pub rule: String,
+    pub passed: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::FileData;
+
+    fn file(tags: &[&str], created: &str, updated: Option<&str>) -> FileData {
+        FileData {
+            tags: tags.iter()
+                .map(|key| (key.to_string(), None))
+                .collect(),
+            comment: None,
+            created: time::parse_datetime(created).unwrap(),
+            updated: updated.map(|v| time::parse_datetime(v).unwrap()),
+        }
+    }
+
+    fn filter(includes: &[&str], excludes: &[&str], match_any: bool) -> FilterArgs {
+        FilterArgs {
+            includes_tags: includes.iter().map(|v| v.parse().unwrap()).collect(),
+            match_any,
+            excludes_tags: excludes.iter().map(|v| v.parse().unwrap()).collect(),
+            updated_after: None,
+            updated_before: None,
+            created_after: None,
+            created_before: None,
+            stale: None,
+            fresh: None,
+        }
+    }
+
+    #[test]
+    fn check_matches_explain() {
+        let date = |v: &str| time::parse_datetime(v).unwrap();
+
+        let files = [
+            file(&[], "2020-01-01T00:00:00Z", None),
+            file(&["a"], "2020-01-01T00:00:00Z", Some("2022-01-01T00:00:00Z")),
+            file(&["a", "b"], "2021-06-01T00:00:00Z", None),
+            file(&["b", "c"], "2019-01-01T00:00:00Z", Some("2023-01-01T00:00:00Z")),
+        ];
+
+        let mut filters = vec![
+            filter(&[], &[], false),
+            filter(&["a"], &[], false),
+            filter(&["a", "b"], &[], false),
+            filter(&["a", "b"], &[], true),
+            filter(&["a", "c"], &["b"], true),
+            filter(&[], &["c"], false),
+        ];
+
+        let mut dated = filter(&["a"], &[], false);
+        dated.updated_after = Some(date("2021-01-01T00:00:00Z"));
+        dated.created_before = Some(date("2021-01-01T00:00:00Z"));
+        filters.push(dated);
+
+        let mut dated = filter(&[], &[], false);
+        dated.updated_before = Some(date("2022-06-01T00:00:00Z"));
+        dated.created_after = Some(date("2019-06-01T00:00:00Z"));
+        dated.stale = Some(date("2022-01-01T00:00:00Z"));
+        filters.push(dated);
+
+        let mut dated = filter(&["b"], &[], false);
+        dated.fresh = Some(date("2021-01-01T00:00:00Z"));
+        filters.push(dated);
+
+        for args in &filters {
+            for data in &files {
+                let explained = args.explain(data).iter().all(|result| result.passed);
+
+                assert_eq!(args.check(data), explained, "{args:?} {data:?}");
+            }
+        }
+
+        assert!(filters[3].check(&files[3]));
+        assert!(!filters[2].check(&files[3]));
+        assert!(!filters[4].check(&files[2]));
+    }
+}
@@ -1,22 +1,33 @@
-use std::cmp::{PartialOrd, Ordering};
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::io::{BufWriter, IsTerminal as _, Write as _};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
+use anyhow::Context as _;
+use anstyle::{AnsiColor, Effects, Style};
 use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+use rayon::prelude::*;
 
+use crate::config;
+use crate::error;
 use crate::logging;
 use crate::tags;
+use crate::filter;
 use crate::path;
+use crate::time::{self, DateTime};
 use crate::db::{self, Db, FileData, MetaContainer};
 
-#[derive(Debug, Eq, Ord)]
+#[derive(Debug, Eq)]
 enum FilterKey<'a> {
     Borrowed(&'a str),
     Owned(Box<str>),
 }
 
-impl<'a> PartialEq for FilterKey<'a> {
+impl PartialEq for FilterKey<'_> {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (FilterKey::Borrowed(a), FilterKey::Borrowed(b)) => a == b,
@@ -27,18 +38,33 @@ impl<'a> PartialEq for FilterKey<'a> {
     }
 }
 
-impl<'a> PartialOrd for FilterKey<'a> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+impl Ord for FilterKey<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
-            (FilterKey::Borrowed(a), FilterKey::Borrowed(b)) => Some(a.cmp(b)),
-            (FilterKey::Borrowed(a), FilterKey::Owned(b)) => Some((**a).cmp(& *b)),
-            (FilterKey::Owned(a), FilterKey::Borrowed(b)) => Some((**a).cmp(*b)),
-            (FilterKey::Owned(a), FilterKey::Owned(b)) => Some(a.cmp(b))
+            (FilterKey::Borrowed(a), FilterKey::Borrowed(b)) => a.cmp(b),
+            (FilterKey::Borrowed(a), FilterKey::Owned(b)) => (**a).cmp(b),
+            (FilterKey::Owned(a), FilterKey::Borrowed(b)) => (**a).cmp(*b),
+            (FilterKey::Owned(a), FilterKey::Owned(b)) => a.cmp(b)
         }
     }
 }
 
-impl<'a> Display for FilterKey<'a> {
+impl PartialOrd for FilterKey<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FilterKey<'_> {
+    fn as_str(&self) -> &str {
+        match self {
+            FilterKey::Borrowed(v) => v,
+            FilterKey::Owned(v) => v,
+        }
+    }
+}
+
+impl Display for FilterKey<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         match self {
             FilterKey::Borrowed(v) => write!(f, "@ {v}"),
@@ -47,17 +73,155 @@ impl<'a> Display for FilterKey<'a> {
     }
 }
 
-type FilteredList<'a> = Vec<(
+type FilteredItem<'a> = (
     FilterKey<'a>,
-    &'a (dyn MetaContainer)
-)>;
+    &'a dyn MetaContainer
+);
 
-#[derive(Debug, Clone, ValueEnum)]
-enum SortBy {
+type FilteredList<'a> = Vec<FilteredItem<'a>>;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub enum SortBy {
     Name,
     Date,
     Created,
     Updated,
+    Tag(String),
+}
+
+impl FromStr for SortBy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "name" => Ok(SortBy::Name),
+            "date" => Ok(SortBy::Date),
+            "created" => Ok(SortBy::Created),
+            "updated" => Ok(SortBy::Updated),
+            _ => if let Some(key) = value.strip_prefix("tag:") {
+                if key.is_empty() {
+                    Err("tag name is empty".to_owned())
+                } else {
+                    Ok(SortBy::Tag(key.to_owned()))
+                }
+            } else {
+                Err(format!("unknown sort \"{value}\", expected name, date, created, updated, or tag:<name>"))
+            }
+        }
+    }
+}
+
+impl Display for SortBy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            SortBy::Name => f.write_str("name"),
+            SortBy::Date => f.write_str("date"),
+            SortBy::Created => f.write_str("created"),
+            SortBy::Updated => f.write_str("updated"),
+            SortBy::Tag(key) => write!(f, "tag:{key}"),
+        }
+    }
+}
+
+impl TryFrom<String> for SortBy {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum Output {
+    /// human readable text
+    Text,
+
+    /// a single json array of entries
+    Json,
+
+    /// one json object per entry per line
+    Jsonl,
+}
+
+/// the json representation of an entry
+#[derive(Serialize)]
+struct EntryJson<'a> {
+    key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Cow<'a, tags::TagsMap>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<&'a str>,
+    created: &'a DateTime,
+    updated: Option<&'a DateTime>,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum PathStyle {
+    /// the path as stored in the db
+    Db,
+
+    /// the path relative to the current working directory
+    Relative,
+
+    /// the absolute path on the file system
+    Absolute,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum ColorChoice {
+    /// color is used if stdout is a terminal and NO_COLOR is not set
+    Auto,
+
+    /// color is always used
+    Always,
+
+    /// color is never used
+    Never,
+}
+
+impl ColorChoice {
+    fn enabled(&self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let no_color = std::env::var_os("NO_COLOR")
+                    .is_some_and(|value| !value.is_empty());
+
+                !no_color && std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+const HEADER_STYLE: Style = Style::new().effects(Effects::BOLD);
+const KEY_STYLE: Style = AnsiColor::Cyan.on_default();
+
+fn value_style(value: &tags::TagValue) -> Style {
+    match value {
+        tags::TagValue::Number(_) |
+        tags::TagValue::Float(_) |
+        tags::TagValue::Size(_) => AnsiColor::Yellow.on_default(),
+        tags::TagValue::Bool(_) => AnsiColor::Magenta.on_default(),
+        tags::TagValue::Url(_) => AnsiColor::Blue.on_default().effects(Effects::UNDERLINE),
+        tags::TagValue::Date(_) => AnsiColor::Green.on_default(),
+        tags::TagValue::Path(_) => AnsiColor::Blue.on_default(),
+        tags::TagValue::Simple(_) |
+        tags::TagValue::List(_) => Style::new(),
+    }
+}
+
+/// wraps the given value in the style if color is enabled
+fn paint<T>(value: T, style: Style, color: bool) -> String
+where
+    T: Display
+{
+    if color {
+        format!("{}{}{}", style.render(), value, style.render_reset())
+    } else {
+        value.to_string()
+    }
 }
 
 #[derive(Debug, Args)]
@@ -70,6 +234,12 @@ pub struct GetArgs {
     #[arg(long, conflicts_with("no_tags"))]
     no_comment: bool,
 
+    /// only outputs the given tags for files
+    ///
+    /// tags that a file does not have are not displayed
+    #[arg(long, value_delimiter(','), value_name("KEYS"), conflicts_with("no_tags"))]
+    only_keys: Vec<String>,
+
     /// retrieves all known data in the db
     #[arg(long)]
     all: bool,
@@ -78,29 +248,116 @@ pub struct GetArgs {
     #[arg(long = "self")]
     self_: bool,
 
-    /// sort by created or updated date
+    /// retrieves data for all entries contained in the specified directories
+    #[arg(short, long, conflicts_with("all"))]
+    recursive: bool,
+
+    /// sort by name, date, created, updated, or tag:<name>
     ///
-    /// sorting will be done in ascending order. if the order of a value cannot
-    /// be determined and there is no other constraint then the order will be
-    /// unspecified
-    #[arg(long, value_delimiter(','), default_value("name"))]
+    /// sorting will be done in ascending order. entries that compare equal
+    /// will be ordered by name. defaults to the `sort_by` value in the db
+    /// config or name.
+    /// tags with number, float, or size values are compared numerically and
+    /// entries without the tag will be placed last
+    #[arg(long, value_delimiter(','))]
     sort_by: Vec<SortBy>,
 
-    /// filters out results that do not contain the desired tags
+    /// stores the given sort as the `sort_by` value in the db config
     ///
-    /// this will be considered a AND operation with exclude tags, so a given
-    /// record must fulfill both include and exclude rules.
-    #[arg(long, value_delimiter(','))]
-    includes_tags: Vec<tags::TagKey>,
+    /// the config is updated and nothing else is displayed. other values in
+    /// the config are kept
+    #[arg(long, value_delimiter(','), value_name("SORT"), conflicts_with("sort_by"))]
+    save_default_sort: Vec<SortBy>,
 
-    /// filters out results that do contain the desired tags
+    /// how paths of entries are displayed
+    #[arg(long, value_enum, default_value("db"))]
+    path_style: PathStyle,
+
+    /// the format of the output
     ///
-    /// this will be considered a AND operation with include tags, so a given
-    /// record must fulfill both include and exclude rules.
-    #[arg(long, value_delimiter(','))]
-    excludes_tags: Vec<tags::TagKey>,
+    /// the total is not displayed for json or jsonl
+    #[arg(long, value_enum, default_value("text"))]
+    output: Output,
+
+    /// the strftime format used to display timestamps in text output
+    ///
+    /// e.g. `%Y-%m-%d` or `%Y-%m-%d %H:%M %Z`. timestamps are displayed in
+    /// local time
+    #[arg(long, value_name("FORMAT"), value_parser(time::parse_date_format))]
+    format_date: Option<String>,
+
+    /// when to use color for text output
+    #[arg(long, value_enum, default_value("auto"))]
+    color: ColorChoice,
+
+    #[command(flatten)]
+    filter: filter::FilterArgs,
+
+    /// only displays the number of results found
+    #[arg(long, conflicts_with_all(["limit", "offset"]))]
+    count: bool,
+
+    /// only displays the paths of matching files, one per line
+    ///
+    /// tags, comments, timestamps, the db itself, and the total are not
+    /// displayed
+    #[arg(long, conflicts_with_all(["count", "no_tags", "no_comment"]))]
+    files_only: bool,
+
+    /// only displays the value of the specified tag, one per line
+    ///
+    /// entries without the tag or with a tag that has no value will not
+    /// display anything
+    #[arg(long, value_name("KEY"), conflicts_with_all(["count", "files_only", "no_tags"]))]
+    tag: Option<String>,
+
+    /// only displays entries that differ from a previously exported db
+    ///
+    /// each entry is displayed with a status of added or modified. removed
+    /// entries, ones in the export that are no longer in the db, are only
+    /// displayed when used with --all. the output is always text and cannot
+    /// be paged with --limit or --offset
+    #[arg(
+        long,
+        value_name("SNAPSHOT"),
+        conflicts_with_all(["count", "files_only", "tag", "output", "limit", "offset"])
+    )]
+    changed_since: Option<PathBuf>,
+
+    /// only displays entries modified since the last time this was used
+    ///
+    /// the time of each run is stored in the .fsm directory. if there is no
+    /// previous run then all entries are displayed
+    #[arg(long)]
+    since_last_run: bool,
+
+    /// does not store the time of this run for --since-last-run
+    #[arg(long, requires("since_last_run"))]
+    no_mark: bool,
+
+    /// prints which filter rules the given file passed or failed instead of
+    /// the normal output
+    #[arg(
+        long,
+        value_name("FILE"),
+        conflicts_with_all(["all", "self_", "recursive", "count", "files_only", "tag", "changed_since", "since_last_run"])
+    )]
+    explain: Option<PathBuf>,
+
+    /// the max number of results to display
+    ///
+    /// the total will still reflect the number of all results found
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// the number of results to skip before displaying
+    #[arg(long, default_value("0"))]
+    offset: usize,
 
     /// the file(s) to retrieve data for
+    ///
+    /// an argument of @<file> will be replaced by the newline separated paths
+    /// in that file
     #[arg(
         trailing_var_arg(true),
         default_value("./")
@@ -108,89 +365,233 @@ pub struct GetArgs {
     files: Vec<PathBuf>,
 }
 
-pub fn get_data(args: GetArgs) -> anyhow::Result<()> {
-    let context = db::Context::cwd_load()?;
+pub fn get_data(mut args: GetArgs) -> anyhow::Result<()> {
+    let context = db::Context::load_readonly()?;
+
+    if !args.save_default_sort.is_empty() {
+        config::save_sort_by(context.fsm_dir(), &args.save_default_sort)?;
+
+        let list: Vec<String> = args.save_default_sort.iter()
+            .map(SortBy::to_string)
+            .collect();
+
+        println!("default sort saved: {}", list.join(","));
+
+        return Ok(());
+    }
+
+    if let Some(file) = &args.explain {
+        return explain_entry(file, &args.filter, &context);
+    }
+
+    args.files = path::expand_arg_files(std::mem::take(&mut args.files))?;
 
     let mut filtered_items: FilteredList = Vec::new();
+    let mut missing = 0usize;
 
-    if (args.self_ || args.all) && check_filter(&context.db, &args) {
+    if (args.self_ || args.all) && args.filter.check(&context.db) {
         filtered_items.push((FilterKey::Borrowed("!SELF"), &context.db));
     }
 
     if args.all {
-        for (key, file) in &context.db.files {
-            if !check_filter(file, &args) {
+        let matched: Vec<(&Box<str>, &FileData)> = context.db.files.par_iter()
+            .filter(|(_, file)| args.filter.check(*file))
+            .collect();
+
+        for (key, file) in matched {
+            filtered_items.push((FilterKey::Borrowed(key), file));
+        }
+    } else if args.recursive {
+        for path_result in context.rel_to_db_list(&args.files) {
+            let Some(rel_path) = logging::log_result(path_result) else {
                 continue;
+            };
+
+            let mut found = false;
+
+            for (key, file) in context.db.files_under(rel_path.db_entry()) {
+                found = true;
+
+                if !args.filter.check(file) {
+                    continue;
+                }
+
+                filtered_items.push((FilterKey::Borrowed(key), file));
             }
 
-            sorted_insert(FilterKey::Borrowed(key), file, &mut filtered_items, &args.sort_by);
+            if !found {
+                println!("\"{}\" not found", rel_path.db_entry());
+                missing += 1;
+            }
         }
     } else {
         for path_result in context.rel_to_db_list(&args.files) {
-            let Some((_path, db_entry, existing)) = get_path_data(path_result, &context.db) else {
+            let Some((_path, db_entry, existing)) = get_path_data(path_result, &context.db, &mut missing) else {
                 continue;
             };
 
-            if !check_filter(existing, &args) {
+            if !args.filter.check(existing) {
                 continue;
             }
 
-            sorted_insert(FilterKey::Owned(db_entry), existing, &mut filtered_items, &args.sort_by);
+            filtered_items.push((FilterKey::Owned(db_entry), existing));
+        }
+    }
+
+    if missing > 0 && error::is_strict() {
+        return Err(error::Reported::NotFound(format!("{missing} paths not found")).into());
+    }
+
+    if args.since_last_run {
+        let run_start = time::datetime_now();
+
+        if let Some(last_run) = read_last_run(&context.last_run_path())? {
+            filtered_items.retain(|(_, data)| *data.modified() > last_run);
+        }
+
+        if !args.no_mark {
+            std::fs::write(context.last_run_path(), run_start.to_rfc3339())
+                .context("failed to store last run")?;
         }
     }
 
+    let mut sort_by = if args.sort_by.is_empty() {
+        context.config()
+            .sort_by
+            .clone()
+            .unwrap_or_else(|| vec![SortBy::Name])
+    } else {
+        args.sort_by.clone()
+    };
+
+    // ties are always broken by name so that repeated runs produce identical
+    // output
+    if !sort_by.iter().any(|by| matches!(by, SortBy::Name)) {
+        sort_by.push(SortBy::Name);
+    }
+
+    // stable sort so that entries that compare equal keep the order they were
+    // found in
+    filtered_items.sort_by(|a, b| compare_items(a, b, &sort_by));
+
     let total = filtered_items.len();
-    let print_title = total > 1;
 
-    for (key, data) in filtered_items {
-        print_data(&key, data, &args, print_title);
+    if args.count {
+        println!("{total}");
+
+        return Ok(());
     }
 
-    println!("Total: {total}");
+    if args.files_only {
+        let page = filtered_items.into_iter()
+            .filter(|(key, _)| key.as_str() != "!SELF")
+            .skip(args.offset)
+            .take(args.limit.unwrap_or(usize::MAX));
 
-    Ok(())
-}
+        for (key, _) in page {
+            println!("{}", format_path(key.as_str(), &args.path_style, context.root()));
+        }
 
-fn check_filter<M>(meta: &M, args: &GetArgs) -> bool
-where
-    M: MetaContainer
-{
-    for check in &args.includes_tags {
-        if !meta.tags().contains_key(check.inner()) {
-            return false;
+        return Ok(());
+    }
+
+    if let Some(snapshot) = &args.changed_since {
+        return print_changed(filtered_items, snapshot, &args, &context.db, context.root());
+    }
+
+    if let Some(key) = &args.tag {
+        let page = filtered_items.into_iter()
+            .skip(args.offset)
+            .take(args.limit.unwrap_or(usize::MAX));
+
+        for (entry, data) in page {
+            match data.tags().get(key) {
+                Some(Some(value)) => println!("{value}"),
+                Some(None) => log::info!("{}: tag \"{}\" has no value", entry, key),
+                None => log::info!("{}: tag \"{}\" not found", entry, key),
+            }
         }
+
+        return Ok(());
     }
 
-    for check in &args.excludes_tags {
-        if meta.tags().contains_key(check.inner()) {
-            return false;
+    let page = filtered_items.into_iter()
+        .skip(args.offset)
+        .take(args.limit.unwrap_or(usize::MAX));
+
+    match args.output {
+        Output::Text => {}
+        Output::Json => {
+            let list: Vec<EntryJson> = page.map(|(key, data)| entry_json(&key, data, &args, context.root()))
+                .collect();
+
+            serde_json::to_writer(std::io::stdout(), &list)
+                .context("failed writing entries to output")?;
+
+            println!();
+
+            return Ok(());
         }
+        Output::Jsonl => {
+            let mut output = BufWriter::new(std::io::stdout().lock());
+
+            for (key, data) in page {
+                serde_json::to_writer(&mut output, &entry_json(&key, data, &args, context.root()))
+                    .context("failed writing entry to output")?;
+
+                writeln!(output).context("failed writing entry to output")?;
+            }
+
+            output.flush().context("failed writing entry to output")?;
+
+            return Ok(());
+        }
+    }
+
+    let print_title = total > 1;
+    let color = args.color.enabled();
+
+    for (key, data) in page {
+        let title = format_key(&key, &args.path_style, context.root());
+
+        print_data(&title, data, &args, print_title, color, &context.config().pinned_tags);
     }
 
-    true
+    println!("Total: {total}");
+
+    Ok(())
 }
 
-fn sorted_insert<'a, M>(key: FilterKey<'a>, meta: &'a M, filtered_items: &mut FilteredList<'a>, sort_by: &[SortBy])
-where
-    M: MetaContainer,
-{
-    let result = filtered_items.binary_search_by(|other| {
-        for by in sort_by {
-            match by {
-                SortBy::Name => match other.0.cmp(&key) {
-                    Ordering::Equal => {},
-                    order => return order,
-                }
-                SortBy::Date => match other.1.modified().cmp(meta.modified()) {
-                    Ordering::Equal => {},
-                    order => return order,
-                }
-                SortBy::Created => match other.1.created().cmp(meta.created()) {
+fn compare_items(a: &FilteredItem<'_>, b: &FilteredItem<'_>, sort_by: &[SortBy]) -> Ordering {
+    for by in sort_by {
+        match by {
+            SortBy::Name => match a.0.cmp(&b.0) {
+                Ordering::Equal => {},
+                order => return order,
+            }
+            SortBy::Date => match a.1.modified().cmp(b.1.modified()) {
+                Ordering::Equal => {},
+                order => return order,
+            }
+            SortBy::Created => match a.1.created().cmp(b.1.created()) {
+                Ordering::Equal => {},
+                order => return order,
+            }
+            SortBy::Updated => match (a.1.updated(), b.1.updated()) {
+                (Some(a_updated), Some(b_updated)) => match a_updated.cmp(b_updated) {
                     Ordering::Equal => {},
                     order => return order,
                 }
-                SortBy::Updated => match (other.1.updated(), meta.updated()) {
-                    (Some(other_updated), Some(meta_updated)) => match other_updated.cmp(meta_updated) {
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => {}
+            }
+            SortBy::Tag(key) => {
+                let a_value = a.1.tags().get(key).and_then(Option::as_ref);
+                let b_value = b.1.tags().get(key).and_then(Option::as_ref);
+
+                match (a_value, b_value) {
+                    (Some(a_value), Some(b_value)) => match compare_tag_values(a_value, b_value) {
                         Ordering::Equal => {},
                         order => return order,
                     }
@@ -200,21 +601,38 @@ where
                 }
             }
         }
+    }
+
+    Ordering::Equal
+}
 
-        Ordering::Equal
-    });
+fn numeric_value(value: &tags::TagValue) -> Option<f64> {
+    match value {
+        tags::TagValue::Number(v) => Some(*v as f64),
+        tags::TagValue::Float(v) => Some(*v),
+        tags::TagValue::Size(v) => Some(*v as f64),
+        _ => None,
+    }
+}
 
-    match result {
-        Ok(index) => filtered_items.insert(index, (key, meta)),
-        Err(index) => filtered_items.insert(index, (key, meta)),
+fn compare_tag_values(a: &tags::TagValue, b: &tags::TagValue) -> Ordering {
+    match (a, b) {
+        (tags::TagValue::Number(a), tags::TagValue::Number(b)) => a.cmp(b),
+        (tags::TagValue::Size(a), tags::TagValue::Size(b)) => a.cmp(b),
+        _ => match (numeric_value(a), numeric_value(b)) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            _ => a.to_string().cmp(&b.to_string()),
+        }
     }
 }
 
 fn get_path_data<'a>(
     path_result: Result<path::RelativePath, path::PathError>,
     db: &'a Db,
+    missing: &mut usize,
 ) -> Option<(Box<Path>, Box<str>, &'a FileData)> {
     let Some(rel_path) = logging::log_result(path_result) else {
+        *missing += 1;
         return None;
     };
 
@@ -222,13 +640,168 @@ fn get_path_data<'a>(
 
     let Some(existing) = db.files.get(&db_entry) else {
         println!("\"{db_entry}\" not found");
+        *missing += 1;
         return None;
     };
 
     Some((path, db_entry, existing))
 }
 
-fn print_data<E, M>(entry: &E, container: &M, args: &GetArgs, print_title: bool)
+/// prints the result of each filter rule for a single entry
+fn explain_entry(file: &Path, filter: &filter::FilterArgs, context: &db::Context) -> anyhow::Result<()> {
+    let (_path, db_entry) = context.rel_to_db(file.to_path_buf())?.into();
+
+    let Some(existing) = context.db.files.get(&db_entry) else {
+        return error::not_found(format!("\"{db_entry}\" not found"));
+    };
+
+    let results = filter.explain(existing);
+
+    println!("{db_entry}");
+
+    if results.is_empty() {
+        println!("    no filter rules given");
+    }
+
+    for result in &results {
+        println!("    {} {}", if result.passed { "pass" } else { "fail" }, result.rule);
+    }
+
+    if filter.check(existing) {
+        println!("included");
+    } else {
+        println!("excluded");
+    }
+
+    Ok(())
+}
+
+/// reads the time of the previous --since-last-run
+fn read_last_run(path: &Path) -> anyhow::Result<Option<DateTime>> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(v) => v,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err).with_context(|| format!("failed reading last run: {}", path.display())),
+    };
+
+    let last_run = chrono::DateTime::parse_from_rfc3339(contents.trim())
+        .with_context(|| format!("invalid last run: {}", path.display()))?;
+
+    Ok(Some(last_run.with_timezone(&chrono::Utc)))
+}
+
+/// loads a db that was written by db export
+fn load_snapshot(given: &Path) -> anyhow::Result<Db> {
+    let file = std::fs::File::open(given)
+        .with_context(|| format!("failed reading snapshot: {}", given.display()))?;
+
+    db::Format::from_path(given)
+        .read_db(std::io::BufReader::new(file))
+        .with_context(|| format!("failed loading snapshot: {}", given.display()))
+}
+
+/// prints each entry that was added or modified since the snapshot, and the
+/// entries removed since the snapshot when --all is given
+fn print_changed(filtered_items: FilteredList<'_>, snapshot: &Path, args: &GetArgs, current: &Db, root: &Path) -> anyhow::Result<()> {
+    let previous = load_snapshot(snapshot)?;
+    let mut total = 0usize;
+
+    for (key, data) in filtered_items {
+        let entry = key.as_str();
+
+        let found: Option<&dyn MetaContainer> = if entry == "!SELF" {
+            Some(&previous)
+        } else {
+            previous.files.get(entry).map(|file| file as &dyn MetaContainer)
+        };
+
+        let status = match found {
+            Some(found) if db::entry_changed(data, found) => "modified",
+            Some(_) => continue,
+            None => "added",
+        };
+
+        println!("{} {}", status, format_key(&key, &args.path_style, root));
+
+        total += 1;
+    }
+
+    if args.all {
+        for entry in previous.files.keys() {
+            if current.files.contains_key(entry) {
+                continue;
+            }
+
+            println!("removed {}", format_key(&FilterKey::Borrowed(entry), &args.path_style, root));
+
+            total += 1;
+        }
+    }
+
+    println!("Total: {total}");
+
+    Ok(())
+}
+
+fn format_path(entry: &str, style: &PathStyle, root: &Path) -> String {
+    match style {
+        PathStyle::Db => entry.to_owned(),
+        PathStyle::Relative => {
+            let full = path::entry_to_path(root, entry);
+
+            path::relative_to(&full, path::get_cwd())
+                .display()
+                .to_string()
+        }
+        PathStyle::Absolute => path::entry_to_path(root, entry)
+            .display()
+            .to_string(),
+    }
+}
+
+fn entry_json<'a>(key: &FilterKey<'_>, data: &'a dyn MetaContainer, args: &GetArgs, root: &Path) -> EntryJson<'a> {
+    let entry = key.as_str();
+
+    EntryJson {
+        key: if entry == "!SELF" {
+            entry.to_owned()
+        } else {
+            format_path(entry, &args.path_style, root)
+        },
+        tags: (!args.no_tags).then(|| select_tags(data.tags(), &args.only_keys)),
+        comment: if args.no_comment {
+            None
+        } else {
+            data.comment()
+        },
+        created: data.created(),
+        updated: data.updated(),
+    }
+}
+
+/// limits the tags to the given keys if any are given
+fn select_tags<'a>(tags: &'a tags::TagsMap, only_keys: &[String]) -> Cow<'a, tags::TagsMap> {
+    if only_keys.is_empty() {
+        Cow::Borrowed(tags)
+    } else {
+        Cow::Owned(tags.iter()
+            .filter(|(key, _)| only_keys.contains(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect())
+    }
+}
+
+fn format_key(key: &FilterKey<'_>, style: &PathStyle, root: &Path) -> String {
+    let entry = key.as_str();
+
+    if entry == "!SELF" {
+        return key.to_string();
+    }
+
+    format!("@ {}", format_path(entry, style, root))
+}
+
+fn print_data<E, M>(entry: &E, container: &M, args: &GetArgs, print_title: bool, color: bool, pinned: &[String])
 where
     M: MetaContainer + ?Sized,
     E: Display + ?Sized,
@@ -238,18 +811,18 @@ where
 
     if !args.no_tags {
         if print_title {
-            println!("{entry}");
+            println!("{}", paint(entry, HEADER_STYLE, color));
             printed_key = true;
         }
 
-        print_tags(container.tags());
+        print_tags(&select_tags(container.tags(), &args.only_keys), pinned, color);
         print_ts = true;
     }
 
     if !args.no_comment {
         if let Some(comment) = container.comment() {
             if print_title && !printed_key {
-                println!("{entry}");
+                println!("{}", paint(entry, HEADER_STYLE, color));
             }
 
             println!("comment: {comment}");
@@ -259,49 +832,71 @@ where
 
     if print_ts {
         let local_offset = chrono::Local;
+        let ts = container.updated()
+            .unwrap_or(container.created())
+            .with_timezone(&local_offset);
 
-        if let Some(updated) = container.updated() {
-            let local_updated = updated.with_timezone(&local_offset);
-
-            println!("{local_updated}");
+        if let Some(format) = &args.format_date {
+            println!("{}", ts.format(format));
         } else {
-            let local_created = container.created()
-                .with_timezone(&local_offset);
-
-            println!("{local_created}");
+            println!("{ts}");
         }
     }
 }
 
-fn print_tags(tags: &tags::TagsMap) {
+fn print_tag(key: &str, value: Option<&tags::TagValue>, max_len: usize, color: bool) {
+    if let Some(value) = value {
+        let aligned = format!("{key:>max_len$}");
+
+        println!("{}: {}", paint(aligned, KEY_STYLE, color), paint(value, value_style(value), color));
+    } else {
+        println!("{}", paint(key, KEY_STYLE, color));
+    }
+}
+
+/// prints tags without values followed by tags with values
+///
+/// pinned tags are printed before all others in the order given
+fn print_tags(tags: &tags::TagsMap, pinned: &[String], color: bool) {
     let mut max_len = 0usize;
     let mut no_value = BinaryHeap::new();
     let mut with_value = BinaryHeap::new();
 
     for (key, value) in tags {
         if value.is_some() {
-            with_value.push(key.clone());
-
             let chars_count = key.chars().count();
 
             if chars_count > max_len {
                 max_len = key.chars().count();
             }
+        }
+
+        if pinned.contains(key) {
+            continue;
+        }
+
+        if value.is_some() {
+            with_value.push(key.clone());
         } else {
             no_value.push(key.clone());
         }
     }
 
+    for key in pinned {
+        if let Some(value) = tags.get(key) {
+            print_tag(key, value.as_ref(), max_len, color);
+        }
+    }
+
     for key in no_value.into_sorted_vec() {
-        println!("{key}");
+        print_tag(&key, None, max_len, color);
     }
 
     for key in with_value.into_sorted_vec() {
         let value = tags.get(&key)
             .unwrap()
-            .as_ref()
-            .unwrap();
+            .as_ref();
 
-        println!("{key:>max_len$}: {value}");
+        print_tag(&key, value, max_len, color);
     }
 }
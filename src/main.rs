@@ -1,9 +1,18 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
 use clap::{Parser, Subcommand};
 
+mod error;
 mod logging;
 mod path;
 mod time;
 mod fs;
+mod filter;
+mod editor;
+mod prompt;
+mod config;
+mod audit;
 
 mod tags;
 mod db;
@@ -11,6 +20,7 @@ mod db;
 mod get;
 mod set;
 mod r#move;
+mod copy;
 mod delete;
 mod open;
 mod coll;
@@ -36,6 +46,44 @@ struct AppArgs {
     /// debug logging for commands
     #[arg(long, conflicts_with("verbose"))]
     debug: bool,
+
+    /// the db to use instead of searching from the current directory
+    ///
+    /// can be a db file, a .fsm directory, or a directory containing a .fsm
+    /// directory
+    #[arg(long, global(true))]
+    db: Option<PathBuf>,
+
+    /// resolves symlinks in file paths before looking them up in the db
+    ///
+    /// tagging a file through a symlink and through its real path will use
+    /// the same entry. paths that do not exist are resolved as far as
+    /// possible
+    #[arg(long, global(true))]
+    resolve_symlinks: bool,
+
+    /// treats missing items and paths that fail to resolve as errors
+    ///
+    /// missing files or collections will exit with a code of 3 after
+    /// displaying the normal message. commands that update the db will abort
+    /// without saving
+    #[arg(long, global(true))]
+    strict: bool,
+
+    /// only looks for a db in the current directory
+    ///
+    /// by default parent directories are searched until a db or a directory
+    /// containing a .fsm-root file is found. can also be enabled by setting
+    /// FSM_NO_ANCESTOR_SEARCH
+    #[arg(long, global(true))]
+    no_ancestor_search: bool,
+
+    /// the directory that db entries are relative to
+    ///
+    /// by default this is the directory containing the .fsm directory. must
+    /// be the current directory or one of its parents
+    #[arg(long, global(true))]
+    assume_root: Option<PathBuf>,
 }
 
 #[derive(Debug, Subcommand)]
@@ -44,11 +92,14 @@ enum Cmd {
     Get(get::GetArgs),
 
     /// updates information for the specified files
-    Set(set::SetArgs),
+    Set(Box<set::SetArgs>),
 
     /// moves a specified entry to another
     Move(r#move::MoveArgs),
 
+    /// copies a specified entry to another
+    Copy(copy::CopyArgs),
+
     /// deletes entries from the database
     Delete(delete::DeleteArgs),
 
@@ -58,13 +109,31 @@ enum Cmd {
     /// manages collections in the db
     Coll(coll::CollectionArgs),
 
+    /// manages tags in the db
+    Tags(tags::TagsArgs),
+
     /// manages db itself
     Db(db::DbArgs),
 }
 
 const RUST_LOG_ENV: &str = "RUST_LOG";
 
-fn main() -> anyhow::Result<()> {
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            if let Some(reported) = err.downcast_ref::<error::Reported>() {
+                ExitCode::from(reported.exit_code())
+            } else {
+                eprintln!("Error: {err:?}");
+
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
+
+fn run() -> anyhow::Result<()> {
     path::set_cwd()?;
 
     let args = AppArgs::parse();
@@ -79,13 +148,27 @@ fn main() -> anyhow::Result<()> {
 
     env_logger::init();
 
+    if let Some(db_path) = &args.db {
+        db::set_db_path(db_path);
+    }
+
+    if let Some(root) = &args.assume_root {
+        db::set_assume_root(root)?;
+    }
+
+    db::set_no_ancestor_search(args.no_ancestor_search);
+    path::set_resolve_symlinks(args.resolve_symlinks);
+    error::set_strict(args.strict);
+
     match args.cmd {
         Cmd::Get(get_args) => get::get_data(get_args),
-        Cmd::Set(set_args) => set::set_data(set_args),
+        Cmd::Set(set_args) => set::set_data(*set_args),
         Cmd::Move(move_args) => r#move::move_data(move_args),
+        Cmd::Copy(copy_args) => copy::copy_data(copy_args),
         Cmd::Delete(delete_args) => delete::delete_data(delete_args),
         Cmd::Open(open_args) => open::open(open_args),
         Cmd::Coll(coll_args) => coll::manage(coll_args),
+        Cmd::Tags(tags_args) => tags::manage(tags_args),
         Cmd::Db(db_args) => db::manage(db_args),
     }
 }
@@ -1,10 +1,26 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Context as _;
-use clap::Args;
+use clap::{Args, ValueEnum};
 
 use crate::db::{self, MetaContainer as _};
 use crate::fs;
+use crate::tags;
+
+/// determines what happens when the source and destination both contain the
+/// same data
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum OnConflict {
+    /// the source value replaces the destination value
+    #[default]
+    Overwrite,
+
+    /// the destination value is kept
+    Keep,
+
+    /// the operation fails
+    Error,
+}
 
 #[derive(Debug, Args)]
 pub struct MoveArgs {
@@ -34,7 +50,17 @@ pub struct MoveArgs {
 
     /// the destination file item
     #[arg(short, long,required_unless_present("to_self"))]
-    to: Option<PathBuf>
+    to: Option<PathBuf>,
+
+    /// what to do when the destination already has a tag or comment from
+    /// the source
+    #[arg(long, value_enum, default_value_t)]
+    on_conflict: OnConflict,
+
+    /// also replaces the source with the destination in every collection the
+    /// source is a member of
+    #[arg(long, requires_all(["from", "to"]))]
+    collections: bool,
 }
 
 fn get_src_entry(context: &mut db::Context, path: PathBuf) -> anyhow::Result<db::FileData> {
@@ -46,7 +72,7 @@ fn get_src_entry(context: &mut db::Context, path: PathBuf) -> anyhow::Result<db:
         .with_context(|| format!("source not found in db: {}", src_path.display()))
 }
 
-fn get_dst_entry<'a>(context: &'a mut db::Context, path: PathBuf, check_exists: bool) -> anyhow::Result<&'a mut db::FileData> {
+pub fn get_dst_entry(context: &mut db::Context, path: PathBuf, check_exists: bool) -> anyhow::Result<&mut db::FileData> {
     let (dst_path, dst_entry) = context.rel_to_db(path)?.into();
 
     if check_exists && !fs::check_exists(&dst_path)? {
@@ -60,9 +86,80 @@ fn get_dst_entry<'a>(context: &'a mut db::Context, path: PathBuf, check_exists:
         .or_default())
 }
 
+/// merges the source tags into the destination tags
+pub fn merge_tags(dst: &mut tags::TagsMap, src: tags::TagsMap, on_conflict: OnConflict) -> anyhow::Result<()> {
+    for (key, value) in src {
+        if dst.contains_key(&key) {
+            match on_conflict {
+                OnConflict::Overwrite => {}
+                OnConflict::Keep => {
+                    log::info!("keeping existing tag: {}", key);
+                    continue;
+                }
+                OnConflict::Error => {
+                    return Err(anyhow::anyhow!("tag \"{}\" already exists in the destination", key));
+                }
+            }
+        }
+
+        dst.insert(key, value);
+    }
+
+    Ok(())
+}
+
+/// merges the source comment into the destination comment
+pub fn merge_comment(dst: &mut Option<String>, src: Option<String>, on_conflict: OnConflict) -> anyhow::Result<()> {
+    let Some(comment) = src else {
+        log::info!("comment is empty");
+        return Ok(());
+    };
+
+    if dst.is_some() {
+        match on_conflict {
+            OnConflict::Overwrite => {}
+            OnConflict::Keep => {
+                log::info!("keeping existing comment");
+                return Ok(());
+            }
+            OnConflict::Error => {
+                return Err(anyhow::anyhow!("comment already exists in the destination"));
+            }
+        }
+    }
+
+    *dst = Some(comment);
+
+    Ok(())
+}
+
+/// replaces the source entry with the destination entry in all collections
+/// that contain it
+fn move_collections(context: &mut db::Context, from: &Path, to: &Path) -> anyhow::Result<()> {
+    let src_rel = context.rel_to_db(from.to_path_buf())?;
+    let dst_rel = context.rel_to_db(to.to_path_buf())?;
+    let dst_entry: Box<str> = dst_rel.db_entry().into();
+
+    for (name, coll) in context.db.collections.iter_mut() {
+        if coll.remove(src_rel.db_entry()) {
+            log::info!("moving collection membership: {}", name);
+
+            coll.insert(dst_entry.clone());
+        }
+    }
+
+    Ok(())
+}
+
 pub fn move_data(args: MoveArgs) -> anyhow::Result<()> {
     let mut context = db::Context::cwd_load()?;
 
+    if args.collections {
+        if let (Some(from), Some(to)) = (&args.from, &args.to) {
+            move_collections(&mut context, from, to)?;
+        }
+    }
+
     if args.tags {
         let src_tags = if let Some(from) = args.from {
             get_src_entry(&mut context, from)?.take_tags()
@@ -73,14 +170,15 @@ pub fn move_data(args: MoveArgs) -> anyhow::Result<()> {
         };
 
         if let Some(to) = args.to {
-            get_dst_entry(&mut context, to, args.exists)?
-                .tags
-                .extend(src_tags);
+            let found = get_dst_entry(&mut context, to, args.exists)?;
+
+            merge_tags(&mut found.tags, src_tags, args.on_conflict)?;
         } else {
             log::info!("updating db");
 
             context.db.update_ts();
-            context.db.tags.extend(src_tags);
+
+            merge_tags(&mut context.db.tags, src_tags, args.on_conflict)?;
         }
     } else if args.comment {
         let src_comment = if let Some(from) = args.from {
@@ -94,19 +192,11 @@ pub fn move_data(args: MoveArgs) -> anyhow::Result<()> {
         if let Some(to) = args.to {
             let found = get_dst_entry(&mut context, to, args.exists)?;
 
-            if let Some(comment) = src_comment {
-                found.comment = Some(comment);
-            } else {
-                log::info!("comment is empty");
-            }
+            merge_comment(&mut found.comment, src_comment, args.on_conflict)?;
         } else {
             log::info!("updating db");
 
-            if let Some(comment) = src_comment {
-                context.db.comment = Some(comment);
-            } else {
-                log::info!("comment is empty");
-            }
+            merge_comment(&mut context.db.comment, src_comment, args.on_conflict)?;
         }
     } else {
         let (src_tags, src_comment) = if let Some(from) = args.from {
@@ -120,20 +210,15 @@ pub fn move_data(args: MoveArgs) -> anyhow::Result<()> {
         if let Some(to) = args.to {
             let found = get_dst_entry(&mut context, to, args.exists)?;
 
-            if let Some(comment) = src_comment {
-                found.comment = Some(comment);
-            }
-
-            found.tags.extend(src_tags);
+            merge_comment(&mut found.comment, src_comment, args.on_conflict)?;
+            merge_tags(&mut found.tags, src_tags, args.on_conflict)?;
         } else {
             log::info!("updating db");
 
             context.db.update_ts();
-            context.db.tags.extend(src_tags);
 
-            if let Some(comment) = src_comment {
-                context.db.comment = Some(comment);
-            }
+            merge_tags(&mut context.db.tags, src_tags, args.on_conflict)?;
+            merge_comment(&mut context.db.comment, src_comment, args.on_conflict)?;
         }
     }
 
@@ -1,16 +1,22 @@
-use std::path::PathBuf;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 
-use clap::Args;
+use clap::{Args, ArgGroup};
 use anyhow::Context;
+use path_absolutize::Absolutize as _;
 
+use crate::fs;
 use crate::logging;
+use crate::path;
 use crate::tags;
 use crate::db;
+use crate::error;
 
 #[derive(Debug, Args)]
+#[command(group(ArgGroup::new("value").args(["tag", "all_urls"])))]
 pub struct OpenArgs {
     /// attempts to open up a tag in the db itself
-    #[arg(long, requires("tag"))]
+    #[arg(long, requires("value"))]
     self_: bool,
 
     /// name of the collection to open
@@ -21,6 +27,29 @@ pub struct OpenArgs {
     #[arg(short, long)]
     tag: Option<String>,
 
+    /// opens every url tag for the specified files
+    #[arg(long)]
+    all_urls: bool,
+
+    /// also opens each file of the collection along with the tag
+    #[arg(long, requires_all(["coll", "tag"]))]
+    also_file: bool,
+
+    /// the max number of urls and files that will be opened
+    ///
+    /// if more would be opened then nothing is opened
+    #[arg(long, default_value_t = 20)]
+    max_open: usize,
+
+    /// opens everything even if there are more than --max-open
+    #[arg(long)]
+    force: bool,
+
+    /// prints the urls and files that would be opened instead of opening
+    /// them
+    #[arg(long)]
+    print: bool,
+
     /// the list of files to open
     ///
     /// if a collection has been specified then a list of files is not needed.
@@ -31,21 +60,33 @@ pub struct OpenArgs {
     files: Vec<PathBuf>,
 }
 
-pub fn open(args: OpenArgs) -> anyhow::Result<()> {
-    let context = db::Context::cwd_load()?;
+/// an item that will be opened
+struct Launch {
+    target: OsString,
+    kind: &'static str,
+}
 
-    if args.self_ {
-        let tag = args.tag.as_ref().unwrap();
+type Launches = Vec<Launch>;
 
-        if let Some(value) = retrieve_tag_value("ROOT", tag, &context.db.tags) {
-            open_tag("ROOT", tag, value);
+/// resolves all of the urls and files to open for the given args
+fn collect_launches(args: &OpenArgs, context: &db::Context) -> anyhow::Result<Launches> {
+    let mut launches = Launches::new();
+
+    if args.self_ {
+        if let Some(tag) = &args.tag {
+            if let Some(value) = retrieve_tag_value("ROOT", tag, &context.db.tags) {
+                open_tag(context.root(), "ROOT", tag, value, &mut launches);
+            }
+        } else {
+            open_all_urls("ROOT", &context.db.tags, &mut launches);
         }
     }
 
     if let Some(name) = &args.coll {
         let Some(coll) = context.db.collections.get(name) else {
-            println!("collection not found");
-            return Ok(());
+            error::not_found("collection not found")?;
+
+            return Ok(launches);
         };
 
         for file in coll {
@@ -56,16 +97,21 @@ pub fn open(args: OpenArgs) -> anyhow::Result<()> {
                 };
 
                 if let Some(value) = retrieve_tag_value(file, tag, &existing.tags) {
-                    open_tag(file, tag, value);
+                    open_tag(context.root(), file, tag, value, &mut launches);
                 }
-            } else {
-                let full_path = context.root().join(&**file);
 
-                log::info!("opening file: {}", full_path.display());
-
-                if let Err(err) = open::that_detached(&full_path).context("failed to open file") {
-                    println!("{}", err);
+                if args.also_file {
+                    open_file(context.root(), file, &mut launches);
                 }
+            } else if args.all_urls {
+                let Some(existing) = context.db.files.get(file) else {
+                    log::info!("file not found in db: {}", file);
+                    continue;
+                };
+
+                open_all_urls(file, &existing.tags, &mut launches);
+            } else {
+                open_file(context.root(), file, &mut launches);
             }
         }
     } else if let Some(tag) = &args.tag {
@@ -82,14 +128,98 @@ pub fn open(args: OpenArgs) -> anyhow::Result<()> {
             };
 
             if let Some(value) = retrieve_tag_value(&db_entry, tag, &existing.tags) {
-                open_tag(&db_entry, tag, value);
+                open_tag(context.root(), &db_entry, tag, value, &mut launches);
             }
         }
+    } else if args.all_urls {
+        for path_result in context.rel_to_db_list(&args.files) {
+            let Some(rel_path) = logging::log_result(path_result) else {
+                continue;
+            };
+
+            let (_path, db_entry) = rel_path.into();
+
+            let Some(existing) = context.db.files.get(&db_entry) else {
+                log::info!("file not found in db: {}", db_entry);
+                continue;
+            };
+
+            open_all_urls(&db_entry, &existing.tags, &mut launches);
+        }
+    }
+
+    Ok(launches)
+}
+
+pub fn open(args: OpenArgs) -> anyhow::Result<()> {
+    let context = db::Context::load_readonly()?;
+    let launches = collect_launches(&args, &context)?;
+
+    if args.print {
+        for launch in launches {
+            println!("{}", launch.target.to_string_lossy());
+        }
+
+        return Ok(());
+    }
+
+    if launches.len() > args.max_open && !args.force {
+        return Err(anyhow::anyhow!(
+            "{} items would be opened which is more than the limit of {}. use --max-open or --force to open them",
+            launches.len(),
+            args.max_open
+        ));
+    }
+
+    for launch in launches {
+        log::info!("opening {}: {}", launch.kind, launch.target.to_string_lossy());
+
+        if let Err(err) = open::that_detached(&launch.target)
+            .with_context(|| format!("failed to open {}", launch.kind)) {
+            println!("{}", err);
+        }
     }
 
     Ok(())
 }
 
+fn open_file(root: &Path, file: &str, launches: &mut Launches) {
+    let full_path = path::entry_to_path(root, file);
+
+    launches.push(Launch {
+        target: full_path.into(),
+        kind: "file",
+    });
+}
+
+fn open_all_urls(file: &str, map: &tags::TagsMap, launches: &mut Launches) {
+    let mut opened = 0usize;
+
+    for (tag, value) in map {
+        let Some(tags::TagValue::Url(url)) = value else {
+            continue;
+        };
+
+        if !tags::url_scheme_allowed(url) {
+            println!("{} {} url scheme \"{}\" is not allowed", file, tag, url.scheme());
+            continue;
+        }
+
+        launches.push(Launch {
+            target: url.as_str().into(),
+            kind: "url",
+        });
+
+        opened += 1;
+    }
+
+    if opened == 0 {
+        log::info!("{} has no url tags", file);
+    } else {
+        log::info!("found {} urls for {}", opened, file);
+    }
+}
+
 fn retrieve_tag_value<'a>(file: &str, tag: &str, map: &'a tags::TagsMap) -> Option<&'a tags::TagValue> {
     let Some(maybe) = map.get(tag) else {
         log::info!("{} {} does not exist", file, tag);
@@ -104,18 +234,82 @@ fn retrieve_tag_value<'a>(file: &str, tag: &str, map: &'a tags::TagsMap) -> Opti
     Some(value)
 }
 
-fn open_tag(file: &str, tag: &str, value: &tags::TagValue) {
-    let url = match value {
-        tags::TagValue::Url(url) => url.to_string(),
-        _ => {
-            log::info!("{} {} is not a valid url", file, tag);
-            return;
+/// attempts to resolve a simple tag value as a path relative to the db root
+///
+/// the resolved path must exist and be contained within the root
+fn resolve_simple_path(root: &Path, value: &str) -> Option<PathBuf> {
+    let resolved = match Path::new(value).absolutize_from(root) {
+        Ok(v) => v.into_owned(),
+        Err(err) => {
+            log::info!("failed to resolve path {}: {}", value, err);
+            return None;
         }
     };
 
-    log::info!("opening tag \"{}\" for file \"{}\"", tag, file);
+    if !resolved.starts_with(root) {
+        log::info!("{} is not contained in the db root", resolved.display());
+        return None;
+    }
+
+    match fs::check_exists(&resolved) {
+        Ok(true) => Some(resolved),
+        Ok(false) => {
+            log::info!("{} does not exist", resolved.display());
+            None
+        }
+        Err(err) => {
+            log::info!("failed to check {}: {}", resolved.display(), err);
+            None
+        }
+    }
+}
+
+fn open_tag(root: &Path, file: &str, tag: &str, value: &tags::TagValue, launches: &mut Launches) {
+    match value {
+        tags::TagValue::Url(url) => {
+            if !tags::url_scheme_allowed(url) {
+                println!("{} {} url scheme \"{}\" is not allowed", file, tag, url.scheme());
+                return;
+            }
+
+            launches.push(Launch {
+                target: url.as_str().into(),
+                kind: "url",
+            });
+        }
+        tags::TagValue::Simple(simple) => {
+            let Some(path) = resolve_simple_path(root, simple) else {
+                log::info!("{} {} is not a valid url or file", file, tag);
+                return;
+            };
 
-    if let Err(err) = open::that_detached(&url).context("failed to open url") {
-        println!("{}", err);
+            launches.push(Launch {
+                target: path.into(),
+                kind: "file",
+            });
+        }
+        tags::TagValue::Path(entry) => {
+            let path = path::entry_to_path(root, entry);
+
+            match fs::check_exists(&path) {
+                Ok(true) => {}
+                Ok(false) => {
+                    println!("{} {} path does not exist: {}", file, tag, path.display());
+                    return;
+                }
+                Err(err) => {
+                    println!("{} {} failed to check path: {}", file, tag, err);
+                    return;
+                }
+            }
+
+            launches.push(Launch {
+                target: path.into(),
+                kind: "file",
+            });
+        }
+        _ => {
+            log::info!("{} {} is not a valid url", file, tag);
+        }
     }
 }
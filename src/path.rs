@@ -1,3 +1,4 @@
+use std::io::Read as _;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
@@ -20,6 +21,42 @@ pub fn get_cwd() -> &'static Path {
     CWD.get().unwrap()
 }
 
+static RESOLVE_SYMLINKS: OnceLock<bool> = OnceLock::new();
+
+/// enables resolving symlinks when converting paths to db entries
+pub fn set_resolve_symlinks(resolve: bool) {
+    let _ = RESOLVE_SYMLINKS.set(resolve);
+}
+
+fn get_resolve_symlinks() -> bool {
+    RESOLVE_SYMLINKS.get().copied().unwrap_or(false)
+}
+
+/// resolves symlinks in the given absolute path
+///
+/// if the path does not exist then the closest existing ancestor will be
+/// resolved and the remaining components appended to it. if nothing can be
+/// resolved then the path is returned as is
+fn resolve_symlinks(given: &Path) -> PathBuf {
+    for ancestor in given.ancestors() {
+        let Ok(resolved) = std::fs::canonicalize(ancestor) else {
+            continue;
+        };
+
+        let Ok(rest) = given.strip_prefix(ancestor) else {
+            break;
+        };
+
+        if rest.as_os_str().is_empty() {
+            return resolved;
+        } else {
+            return resolved.join(rest);
+        }
+    }
+
+    given.to_path_buf()
+}
+
 #[derive(Debug, Error)]
 pub enum PathError {
     #[error("file and db do not share a common root: {}", .0.display())]
@@ -32,6 +69,142 @@ pub enum PathError {
     Io(std::io::Error, PathBuf),
 }
 
+/// reads the contents of the given file or stdin if the path is `-`
+pub fn read_input(given: &Path) -> anyhow::Result<String> {
+    if given == Path::new("-") {
+        let mut buf = String::new();
+
+        std::io::stdin()
+            .read_to_string(&mut buf)
+            .context("failed reading from stdin")?;
+
+        Ok(buf)
+    } else {
+        std::fs::read_to_string(given)
+            .with_context(|| format!("failed reading file: {}", given.display()))
+    }
+}
+
+/// reads a list of newline separated paths from the given file
+///
+/// a path of `-` will read from stdin. empty lines are ignored. relative paths
+/// will be resolved from the current working directory when converted to db
+/// entries
+pub fn read_path_list(given: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let contents = read_input(given)?;
+
+    Ok(contents.lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// expands any `@file` arguments into the paths listed in that file
+///
+/// the listed paths are read with [`read_path_list`]. paths that start with
+/// `@` can be given by prefixing them with `./`
+pub fn expand_arg_files(given: Vec<PathBuf>) -> anyhow::Result<Vec<PathBuf>> {
+    let mut rtn = Vec::with_capacity(given.len());
+
+    for arg in given {
+        if let Some(list_file) = arg.to_str().and_then(|v| v.strip_prefix('@')) {
+            log::info!("reading paths from {}", list_file);
+
+            rtn.extend(read_path_list(Path::new(list_file))?);
+        } else {
+            rtn.push(arg);
+        }
+    }
+
+    Ok(rtn)
+}
+
+/// converts a db entry back into a full path under the given root
+///
+/// the forward slashes of the db entry are converted to the platform separator
+pub fn entry_to_path(root: &Path, db_entry: &str) -> PathBuf {
+    if db_entry.is_empty() {
+        return root.to_path_buf();
+    }
+
+    if std::path::MAIN_SEPARATOR != '/' {
+        root.join(db_entry.replace('/', std::path::MAIN_SEPARATOR_STR))
+    } else {
+        root.join(db_entry)
+    }
+}
+
+/// creates a path to the given absolute path relative to the base directory
+///
+/// both paths are expected to be absolute
+pub fn relative_to(path: &Path, base: &Path) -> PathBuf {
+    let mut path_iter = path.components().peekable();
+    let mut base_iter = base.components().peekable();
+
+    while let (Some(a), Some(b)) = (path_iter.peek(), base_iter.peek()) {
+        if a != b {
+            break;
+        }
+
+        path_iter.next();
+        base_iter.next();
+    }
+
+    let mut rtn = PathBuf::new();
+
+    for _ in base_iter {
+        rtn.push("..");
+    }
+
+    rtn.extend(path_iter);
+
+    if rtn.as_os_str().is_empty() {
+        rtn.push(".");
+    }
+
+    rtn
+}
+
+/// strips the root from the given path
+///
+/// windows paths are case insensitive so the components are compared without
+/// case
+#[cfg(windows)]
+fn strip_root<'a>(path: &'a Path, root: &Path) -> Option<&'a Path> {
+    let mut path_iter = path.components();
+
+    for root_comp in root.components() {
+        let path_comp = path_iter.next()?;
+
+        if path_comp.as_os_str().to_string_lossy().to_lowercase() !=
+            root_comp.as_os_str().to_string_lossy().to_lowercase() {
+            return None;
+        }
+    }
+
+    Some(path_iter.as_path())
+}
+
+#[cfg(not(windows))]
+fn strip_root<'a>(path: &'a Path, root: &Path) -> Option<&'a Path> {
+    path.strip_prefix(root).ok()
+}
+
+/// folds the case of a db entry so that the same file always produces the
+/// same entry
+///
+/// only windows is case insensitive, other platforms are left as is
+#[cfg(windows)]
+pub fn fold_case(db_entry: String) -> String {
+    db_entry.to_lowercase()
+}
+
+#[cfg(not(windows))]
+pub fn fold_case(db_entry: String) -> String {
+    db_entry
+}
+
 pub struct RelativePath {
     full: Box<Path>,
     db_entry: Box<str>,
@@ -50,7 +223,13 @@ impl RelativePath {
             given.clone()
         };
 
-        let Ok(from_root) = rtn.strip_prefix(root) else {
+        let (rtn, root) = if get_resolve_symlinks() {
+            (resolve_symlinks(&rtn), resolve_symlinks(root))
+        } else {
+            (rtn, root.to_path_buf())
+        };
+
+        let Some(from_root) = strip_root(&rtn, &root) else {
             return Err(PathError::InvalidPrefix(rtn.clone()));
         };
 
@@ -59,9 +238,9 @@ impl RelativePath {
         };
 
         let db_entry = if std::path::MAIN_SEPARATOR != '/' {
-            utf_from_root.replace(std::path::MAIN_SEPARATOR_STR, "/").into()
+            fold_case(utf_from_root.replace(std::path::MAIN_SEPARATOR_STR, "/")).into()
         } else {
-            utf_from_root.into()
+            fold_case(utf_from_root.to_owned()).into()
         };
 
         Ok(RelativePath {
@@ -95,7 +274,7 @@ pub struct RelativePathList<'a> {
 }
 
 impl<'a> RelativePathList<'a> {
-    pub fn new(root: Box<Path>, path_list: &'a Vec<PathBuf>) -> Self {
+    pub fn new(root: Box<Path>, path_list: &'a [PathBuf]) -> Self {
         RelativePathList {
             iter: path_list.iter(),
             root
@@ -103,13 +282,11 @@ impl<'a> RelativePathList<'a> {
     }
 }
 
-impl<'a> std::iter::Iterator for RelativePathList<'a> {
+impl std::iter::Iterator for RelativePathList<'_> {
     type Item = Result<RelativePath, PathError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let Some(path) = self.iter.next() else {
-            return None;
-        };
+        let path = self.iter.next()?;
 
         Some(RelativePath::from_root(&self.root, path))
     }
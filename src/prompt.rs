@@ -0,0 +1,18 @@
+use std::io::Write as _;
+
+use anyhow::Context;
+
+/// asks the user a yes or no question, defaulting to no
+pub fn confirm(question: &str) -> anyhow::Result<bool> {
+    print!("{} [y/N] ", question);
+
+    std::io::stdout().flush()
+        .context("failed to write prompt")?;
+
+    let mut answer = String::new();
+
+    std::io::stdin().read_line(&mut answer)
+        .context("failed to read confirmation")?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
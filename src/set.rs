@@ -1,9 +1,17 @@
+use std::collections::BTreeMap;
+use std::io::Read as _;
 use std::path::PathBuf;
 
+use anyhow::Context as _;
 use clap::Args;
+use regex::Regex;
 
 use crate::logging;
+use crate::path;
+use crate::editor;
+use crate::error;
 use crate::tags;
+use crate::time;
 use crate::db::{self, MetaContainer as _};
 
 #[derive(Debug, Args)]
@@ -26,6 +34,19 @@ pub struct SetArgs {
     )]
     tag: Vec<tags::Tag>,
 
+    /// set a string tag to the files
+    ///
+    /// similar to the regular tag but the value will always be stored as a
+    /// string and will not be parsed into any other type. useful for values
+    /// such as zip codes or version strings
+    #[arg(
+        short = 's',
+        long,
+        conflicts_with_all(["drop_all"]),
+        value_parser(tags::parse_str_tag)
+    )]
+    tag_str: Vec<tags::Tag>,
+
     /// set a url tag to the files
     ///
     /// similar to a regular tag but if the tag value is not a valid url then
@@ -48,6 +69,18 @@ pub struct SetArgs {
     )]
     tag_num: Vec<tags::Tag>,
 
+    /// set a float tag to the files
+    ///
+    /// similar to the regular tag but if the tag value is not a valid floating
+    /// point number then the operation will fail
+    #[arg(
+        short = 'f',
+        long,
+        conflicts_with_all(["drop_all"]),
+        value_parser(tags::parse_float_tag)
+    )]
+    tag_float: Vec<tags::Tag>,
+
     /// set a bool tag to the files
     ///
     /// similar to the regular tag but if the tag value is not a valid bool
@@ -59,6 +92,77 @@ pub struct SetArgs {
     )]
     tag_bool: Vec<tags::Tag>,
 
+    /// set a date tag to the files
+    ///
+    /// accepts RFC3339 or YYYY-MM-DD values. values without an offset are
+    /// assumed to be in local time. if the tag value is not a valid date then
+    /// the operation will fail
+    #[arg(
+        long,
+        conflicts_with_all(["drop_all"]),
+        value_parser(tags::parse_date_tag)
+    )]
+    tag_date: Vec<tags::Tag>,
+
+    /// set a size tag to the files
+    ///
+    /// accepts a byte count with an optional K, M, G, T or KiB, MiB, GiB, TiB
+    /// suffix. the value is stored as bytes. if the tag value is not a valid
+    /// size then the operation will fail
+    #[arg(
+        long,
+        conflicts_with_all(["drop_all"]),
+        value_parser(tags::parse_size_tag)
+    )]
+    tag_size: Vec<tags::Tag>,
+
+    /// set a path tag to the files
+    ///
+    /// the path is resolved from the current working directory and stored
+    /// relative to the db root. if the path is not contained in the db root
+    /// then the operation will fail
+    #[arg(
+        long,
+        conflicts_with_all(["drop_all"]),
+        value_parser(tags::parse_path_tag)
+    )]
+    tag_path: Vec<tags::Tag>,
+
+    /// adds a value to a tag of the files
+    ///
+    /// if the tag already has a different value then the tag will become a
+    /// list containing both the existing and new values. values already in
+    /// the list will not be added again
+    #[arg(
+        short = 'a',
+        long,
+        conflicts_with_all(["drop_all"]),
+        value_parser(tags::parse_tag)
+    )]
+    add: Vec<tags::Tag>,
+
+    /// set a string tag to the files with the value read from stdin
+    ///
+    /// the trailing newline is removed unless --raw is given
+    #[arg(
+        long,
+        value_name("KEY"),
+        conflicts_with_all(["tag", "tag_str", "tag_url", "tag_num", "tag_float", "tag_bool", "tag_date", "tag_size", "tag_path", "add", "drop_all", "comment_stdin"])
+    )]
+    tag_from_stdin: Option<String>,
+
+    /// keeps the value read by --tag-from-stdin as is
+    #[arg(long, requires("tag_from_stdin"))]
+    raw: bool,
+
+    /// set tags to the files from a json object
+    ///
+    /// e.g. `{"author": "x", "year": 2020}`. strings are stored as string
+    /// values, numbers as integers or floats, null as a tag without a value,
+    /// and arrays of values as lists
+    #[arg(long, value_name("JSON"), conflicts_with_all(["drop_all"]))]
+    from_json: Option<String>,
+
     /// remove a tag from the files
     ///
     /// this will remove a tag from the existing list of tags for the
@@ -69,7 +173,7 @@ pub struct SetArgs {
     /// remote all tags from the files
     #[arg(
         long,
-        conflicts_with_all(["tag", "tag_url", "tag_num", "tag_bool", "drop"])
+        conflicts_with_all(["tag", "tag_str", "tag_url", "tag_num", "tag_float", "tag_bool", "tag_date", "tag_size", "tag_path", "add", "drop", "from_json"])
     )]
     drop_all: bool,
 
@@ -81,14 +185,109 @@ pub struct SetArgs {
     #[arg(long, conflicts_with("comment"))]
     drop_comment: bool,
 
+    /// appends to the existing comment of the files
+    ///
+    /// the text will be separated from the existing comment by a newline. if
+    /// no comment exists then one will be created
+    #[arg(long, conflicts_with_all(["comment", "drop_comment"]))]
+    append_comment: Option<String>,
+
+    /// reads the comment for the files from stdin
+    ///
+    /// trailing newlines are removed. if stdin is empty then the comment will
+    /// be removed from the files
+    #[arg(long, conflicts_with_all(["comment", "drop_comment", "append_comment"]))]
+    comment_stdin: bool,
+
+    /// edits the comment in the editor specified by $VISUAL or $EDITOR
+    ///
+    /// only a single file or the db itself can be edited at a time. if the
+    /// editor fails or the comment is unchanged then nothing is updated. an
+    /// empty comment will remove it
+    #[arg(long, conflicts_with_all(["comment", "drop_comment", "append_comment", "comment_stdin", "recursive"]))]
+    edit_comment: bool,
+
+    /// removes the comment from the files only if it contains the given text
+    ///
+    /// files that are not in the db are skipped and only entries that have
+    /// their comment removed are updated. the number of comments removed will
+    /// be displayed
+    #[arg(
+        long,
+        value_name("PATTERN"),
+        conflicts_with_all([
+            "comment", "drop_comment", "append_comment", "comment_stdin", "edit_comment",
+            "tag", "tag_str", "tag_url", "tag_num", "tag_float", "tag_bool", "tag_date", "tag_size", "tag_path",
+            "add", "tag_from_stdin", "from_json", "drop", "drop_all", "set_created", "set_updated", "init", "from_file"
+        ])
+    )]
+    clear_comment_if: Option<String>,
+
+    /// treats the --clear-comment-if pattern as a regex
+    #[arg(long, requires("clear_comment_if"))]
+    regex: bool,
+
     /// sets tags to the db itself
     #[arg(long = "self")]
     self_: bool,
 
+    /// also updates all existing entries contained in the specified
+    /// directories
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// displays the changes that would be made without saving them
+    ///
+    /// entries that do not exist in the db will be marked as new
+    #[arg(long)]
+    dry_run: bool,
+
+    /// does not change the updated timestamp of existing entries
+    ///
+    /// new entries will still have their created timestamp set
+    #[arg(long)]
+    no_touch: bool,
+
+    /// sets the created timestamp of the files
+    ///
+    /// accepts RFC3339 or YYYY-MM-DD values. values without an offset are
+    /// assumed to be in local time
+    #[arg(long, value_parser(time::parse_datetime))]
+    set_created: Option<time::DateTime>,
+
+    /// sets the updated timestamp of the files
+    ///
+    /// accepts the same values as --set-created
+    #[arg(long, value_parser(time::parse_datetime))]
+    set_updated: Option<time::DateTime>,
+
+    /// creates a json db in the current directory if no db is found
+    #[arg(long, conflicts_with("dry_run"))]
+    init: bool,
+
+    /// allows tag keys that contain whitespace or invalid characters
+    ///
+    /// by default tag keys are validated so they can be used with tag
+    /// filters
+    #[arg(long)]
+    lenient: bool,
+
+    /// reads files and tags to set from a file, or stdin with -
+    ///
+    /// each line is a path optionally followed by a tab and a comma separated
+    /// list of tags, e.g. `notes.txt<TAB>todo,priority:2`. empty lines and
+    /// lines starting with # are ignored. any tag or comment options given
+    /// will also be applied to each file
+    #[arg(long)]
+    from_file: Option<PathBuf>,
+
     /// the file(s) to update data for
+    ///
+    /// an argument of @<file> will be replaced by the newline separated paths
+    /// in that file
     #[arg(
         trailing_var_arg(true),
-        required_unless_present("self_")
+        required_unless_present_any(["self_", "from_file"])
     )]
     files: Vec<PathBuf>,
 }
@@ -96,9 +295,39 @@ pub struct SetArgs {
 #[inline]
 fn has_tags(args: &SetArgs) -> bool {
     !args.tag.is_empty() ||
+        !args.tag_str.is_empty() ||
         !args.tag_url.is_empty() ||
         !args.tag_num.is_empty() ||
-        !args.tag_bool.is_empty()
+        !args.tag_float.is_empty() ||
+        !args.tag_bool.is_empty() ||
+        !args.tag_date.is_empty() ||
+        !args.tag_size.is_empty() ||
+        !args.tag_path.is_empty() ||
+        !args.add.is_empty()
+}
+
+/// checks that all of the tag keys given can be used as a [`tags::TagKey`]
+fn validate_keys(args: &SetArgs) -> anyhow::Result<()> {
+    let lists = [
+        &args.tag,
+        &args.tag_str,
+        &args.tag_url,
+        &args.tag_num,
+        &args.tag_float,
+        &args.tag_bool,
+        &args.tag_date,
+        &args.tag_size,
+        &args.tag_path,
+        &args.add,
+    ];
+
+    for (key, _) in lists.into_iter().flatten() {
+        if let Err(err) = key.parse::<tags::TagKey>() {
+            return Err(anyhow::anyhow!("invalid tag key \"{}\": {}. use --lenient to allow it", key, err));
+        }
+    }
+
+    Ok(())
 }
 
 fn update_tags(args: &SetArgs, tags: &mut tags::TagsMap) {
@@ -114,48 +343,524 @@ fn update_tags(args: &SetArgs, tags: &mut tags::TagsMap) {
         }
 
         tags.extend(args.tag.iter().cloned());
+        tags.extend(args.tag_str.iter().cloned());
         tags.extend(args.tag_url.iter().cloned());
         tags.extend(args.tag_num.iter().cloned());
+        tags.extend(args.tag_float.iter().cloned());
         tags.extend(args.tag_bool.iter().cloned());
+        tags.extend(args.tag_date.iter().cloned());
+        tags.extend(args.tag_size.iter().cloned());
+        tags.extend(args.tag_path.iter().cloned());
+
+        for (key, value) in &args.add {
+            let existing = tags.entry(key.clone()).or_default();
+
+            if let Some(value) = value {
+                tags::append_value(existing, value.clone());
+            }
+        }
+    }
+}
+
+/// the pattern a comment must match to be removed
+enum CommentMatch {
+    Contains(String),
+    Regex(Regex),
+}
+
+impl CommentMatch {
+    fn matches(&self, comment: &str) -> bool {
+        match self {
+            CommentMatch::Contains(given) => comment.contains(given.as_str()),
+            CommentMatch::Regex(regex) => regex.is_match(comment),
+        }
+    }
+}
+
+enum CommentOp {
+    Keep,
+    Set(String),
+    Append(String),
+    Drop,
+    DropIf(CommentMatch),
+}
+
+impl CommentOp {
+    fn from_args(args: &SetArgs) -> anyhow::Result<Self> {
+        let op = if args.drop_comment {
+            CommentOp::Drop
+        } else if let Some(given) = &args.comment {
+            CommentOp::Set(given.clone())
+        } else if let Some(given) = &args.append_comment {
+            CommentOp::Append(given.clone())
+        } else if let Some(given) = &args.clear_comment_if {
+            if args.regex {
+                let regex = Regex::new(given)
+                    .context("invalid --clear-comment-if regex")?;
+
+                CommentOp::DropIf(CommentMatch::Regex(regex))
+            } else {
+                CommentOp::DropIf(CommentMatch::Contains(given.clone()))
+            }
+        } else {
+            CommentOp::Keep
+        };
+
+        Ok(op)
+    }
+
+    fn from_stdin() -> anyhow::Result<Self> {
+        let mut buf = String::new();
+
+        std::io::stdin()
+            .read_to_string(&mut buf)
+            .context("failed to read comment from stdin")?;
+
+        let trimmed = buf.trim_end_matches(['\n', '\r']);
+
+        if trimmed.is_empty() {
+            log::info!("stdin is empty, dropping comment");
+
+            Ok(CommentOp::Drop)
+        } else {
+            Ok(CommentOp::Set(trimmed.to_owned()))
+        }
+    }
+
+    fn from_editor(current: Option<&str>) -> anyhow::Result<Self> {
+        let current = current.unwrap_or_default();
+
+        let Some(edited) = editor::edit(current)? else {
+            log::info!("editor failed, comment not changed");
+            return Ok(CommentOp::Keep);
+        };
+
+        let edited = edited.trim_end();
+
+        if edited == current.trim_end() {
+            log::info!("comment not changed");
+
+            Ok(CommentOp::Keep)
+        } else if edited.is_empty() {
+            Ok(CommentOp::Drop)
+        } else {
+            Ok(CommentOp::Set(edited.to_owned()))
+        }
+    }
+}
+
+/// updates the comment with the given op
+///
+/// returns true if the comment was removed by a conditional drop
+fn update_comment(op: &CommentOp, comment: &mut Option<String>) -> bool {
+    match op {
+        CommentOp::Keep => {}
+        CommentOp::Set(given) => *comment = Some(given.clone()),
+        CommentOp::Append(given) => if let Some(existing) = comment {
+            existing.push('\n');
+            existing.push_str(given);
+        } else {
+            *comment = Some(given.clone());
+        }
+        CommentOp::Drop => *comment = None,
+        CommentOp::DropIf(pattern) => if comment.as_deref().is_some_and(|existing| pattern.matches(existing)) {
+            *comment = None;
+
+            return true;
+        }
+    }
+
+    false
+}
+
+fn edit_comment(args: &SetArgs, context: &db::Context) -> anyhow::Result<CommentOp> {
+    if args.self_ {
+        if !args.files.is_empty() {
+            return Err(anyhow::anyhow!("--edit-comment can only be used on a single target"));
+        }
+
+        CommentOp::from_editor(context.db.comment.as_deref())
+    } else {
+        let [file] = args.files.as_slice() else {
+            return Err(anyhow::anyhow!("--edit-comment can only be used on a single target"));
+        };
+
+        let rel_path = context.rel_to_db(file.clone())?;
+        let current = context.db.files.get(rel_path.db_entry())
+            .and_then(|data| data.comment.as_deref());
+
+        CommentOp::from_editor(current)
     }
 }
 
-pub fn set_data(args: SetArgs) -> anyhow::Result<()> {
-    let mut context = db::Context::cwd_load()?;
+type Snapshot = (tags::TagsMap, Option<String>);
+
+fn print_tag(prefix: &str, key: &str, value: Option<&tags::TagValue>) {
+    if let Some(value) = value {
+        println!("    {prefix} {key}: {value}");
+    } else {
+        println!("    {prefix} {key}");
+    }
+}
+
+fn print_changes(
+    status: &str,
+    entry: &str,
+    (before_tags, before_comment): Snapshot,
+    after_tags: &tags::TagsMap,
+    after_comment: Option<&String>,
+) {
+    println!("{status}: {entry}");
+
+    for (key, value) in &before_tags {
+        if !after_tags.contains_key(key) {
+            print_tag("-", key, value.as_ref());
+        }
+    }
+
+    for (key, value) in after_tags {
+        match before_tags.get(key) {
+            Some(before) if before == value => {}
+            Some(before) => {
+                let before = before.as_ref()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default();
+                let after = value.as_ref()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default();
+
+                println!("    ~ {key}: {before} -> {after}");
+            }
+            None => print_tag("+", key, value.as_ref()),
+        }
+    }
+
+    if before_comment.as_ref() != after_comment {
+        if let Some(comment) = after_comment {
+            println!("    comment: {comment}");
+        } else {
+            println!("    comment removed");
+        }
+    }
+}
+
+/// converts the given path tags into db entries
+fn normalize_path_tags(list: &mut [tags::Tag], context: &db::Context) -> anyhow::Result<()> {
+    for (key, value) in list {
+        let Some(tags::TagValue::Path(given)) = value else {
+            continue;
+        };
+
+        let rel_path = context.rel_to_db(PathBuf::from(&*given))
+            .with_context(|| format!("invalid path for tag \"{}\"", key))?;
+
+        *given = rel_path.db_entry().to_owned();
+    }
+
+    Ok(())
+}
+
+/// reads the value for --tag-from-stdin
+fn read_stdin_tag(key: String, raw: bool) -> anyhow::Result<tags::Tag> {
+    let mut buf = String::new();
+
+    std::io::stdin()
+        .read_to_string(&mut buf)
+        .context("failed to read tag value from stdin")?;
+
+    if !raw {
+        let trimmed_len = buf.trim_end_matches(['\n', '\r']).len();
+
+        buf.truncate(trimmed_len);
+    }
+
+    Ok((key, Some(tags::TagValue::Simple(buf))))
+}
+
+/// an entry to update with any additional tags from a --from-file spec
+type Target = (Box<str>, Vec<tags::Tag>);
+
+/// combines targets for the same entry so that each entry is only updated
+/// once
+///
+/// the additional tags of duplicates are added to the first target
+fn dedup_targets(targets: Vec<Target>) -> Vec<Target> {
+    let mut seen: BTreeMap<Box<str>, usize> = BTreeMap::new();
+    let mut rtn: Vec<Target> = Vec::with_capacity(targets.len());
+
+    for (db_entry, spec_tags) in targets {
+        if let Some(index) = seen.get(&db_entry) {
+            log::info!("skipping duplicate entry: {}", db_entry);
+
+            rtn[*index].1.extend(spec_tags);
+        } else {
+            seen.insert(db_entry.clone(), rtn.len());
+            rtn.push((db_entry, spec_tags));
+        }
+    }
+
+    rtn
+}
+
+/// a line from a --from-file spec with its line number
+type SpecLine = (usize, PathBuf, Vec<tags::Tag>);
+
+/// parses the lines of a --from-file spec
+///
+/// invalid lines are reported and counted but do not stop parsing
+fn parse_spec(contents: &str, lenient: bool) -> (Vec<SpecLine>, usize) {
+    let mut lines = Vec::new();
+    let mut invalid = 0usize;
+
+    'lines: for (index, line) in contents.lines().enumerate() {
+        let line_num = index + 1;
+        let line = line.trim_end_matches('\r');
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (path, tag_list) = line.split_once('\t').unwrap_or((line, ""));
+
+        if path.is_empty() {
+            println!("line {}: path is empty", line_num);
+
+            invalid += 1;
+            continue;
+        }
+
+        let mut parsed = Vec::new();
+
+        for token in tag_list.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            match tags::parse_tag(token) {
+                Ok((key, _)) if !lenient && key.parse::<tags::TagKey>().is_err() => {
+                    println!("line {}: invalid tag key \"{}\"", line_num, key);
+
+                    invalid += 1;
+                    continue 'lines;
+                }
+                Ok(tag) => parsed.push(tag),
+                Err(err) => {
+                    println!("line {}: {}", line_num, err);
+
+                    invalid += 1;
+                    continue 'lines;
+                }
+            }
+        }
+
+        lines.push((line_num, PathBuf::from(path), parsed));
+    }
+
+    (lines, invalid)
+}
+
+pub fn set_data(mut args: SetArgs) -> anyhow::Result<()> {
+    if let Some(key) = args.tag_from_stdin.take() {
+        if args.from_file.as_deref() == Some(std::path::Path::new("-")) {
+            return Err(anyhow::anyhow!("--tag-from-stdin cannot be used when --from-file reads from stdin"));
+        }
+
+        args.tag_str.push(read_stdin_tag(key, args.raw)?);
+    }
+
+    if let Some(given) = args.from_json.take() {
+        let parsed = tags::parse_json_tags(&given)
+            .map_err(|err| anyhow::anyhow!("failed to parse --from-json: {}", err))?;
+
+        args.tag.extend(parsed);
+    }
+
+    if !args.lenient {
+        validate_keys(&args)?;
+    }
+
+    let mut context = if args.init {
+        db::Context::cwd_load_or_init(db::Format::Json)?
+    } else {
+        db::Context::cwd_load()?
+    };
+
+    normalize_path_tags(&mut args.tag_path, &context)?;
+
+    args.files = path::expand_arg_files(std::mem::take(&mut args.files))?;
+
+    let comment_op = if args.edit_comment {
+        edit_comment(&args, &context)?
+    } else if args.comment_stdin {
+        CommentOp::from_stdin()?
+    } else {
+        CommentOp::from_args(&args)?
+    };
+
+    let mut cleared = 0usize;
 
     if args.self_ {
+        let snapshot = args.dry_run
+            .then(|| (context.db.tags.clone(), context.db.comment.clone()));
+
         update_tags(&args, &mut context.db.tags);
+        if update_comment(&comment_op, &mut context.db.comment) {
+            cleared += 1;
+        }
+
+        if let Some(created) = args.set_created {
+            context.db.created = created;
+        }
+
+        if let Some(updated) = args.set_updated {
+            context.db.updated = Some(updated);
+        }
 
-        if args.drop_comment {
-            context.db.comment = None;
-        } else if let Some(comment) = &args.comment {
-            context.db.comment = Some(comment.clone());
+        if let Some(snapshot) = snapshot {
+            print_changes("update", "!SELF", snapshot, &context.db.tags, context.db.comment.as_ref());
         }
     }
 
+    let mut targets: Vec<Target> = Vec::new();
+    let mut skipped = 0usize;
+    let mut invalid = 0usize;
+
     for path_result in context.rel_to_db_list(&args.files) {
         let Some(rel_path) = logging::log_result(path_result) else {
+            skipped += 1;
             continue;
         };
 
         let (_path, db_entry) = rel_path.into();
 
+        if args.recursive {
+            let children: Vec<Box<str>> = context.db.files_under(&db_entry)
+                .filter(|(key, _)| **key != db_entry)
+                .map(|(key, _)| key.clone())
+                .collect();
+
+            targets.push((db_entry, Vec::new()));
+            targets.extend(children.into_iter().map(|key| (key, Vec::new())));
+        } else {
+            targets.push((db_entry, Vec::new()));
+        }
+    }
+
+    if let Some(from_file) = &args.from_file {
+        let (lines, invalid_lines) = parse_spec(&path::read_input(from_file)?, args.lenient);
+
+        invalid += invalid_lines;
+
+        for (line_num, given, spec_tags) in lines {
+            match context.rel_to_db(given) {
+                Ok(rel_path) => {
+                    let (_path, db_entry) = rel_path.into();
+
+                    targets.push((db_entry, spec_tags));
+                }
+                Err(err) => {
+                    println!("line {}: {}", line_num, err);
+
+                    skipped += 1;
+                }
+            }
+        }
+    }
+
+    if error::is_strict() {
+        if skipped > 0 {
+            return Err(anyhow::anyhow!("failed to resolve {} paths, nothing was updated", skipped));
+        }
+
+        if invalid > 0 {
+            return Err(anyhow::anyhow!("failed to parse {} lines, nothing was updated", invalid));
+        }
+    }
+
+    for (db_entry, spec_tags) in dedup_targets(targets) {
         log::info!("retrieving entry: {}", db_entry);
 
-        let entry = context.db.files.entry(db_entry)
-            .and_modify(db::FileData::update_ts)
-            .or_default();
+        if matches!(comment_op, CommentOp::DropIf(_)) {
+            let Some(entry) = context.db.files.get_mut(&db_entry) else {
+                log::info!("skipping {}, not found in db", db_entry);
+                continue;
+            };
+
+            let snapshot = args.dry_run
+                .then(|| (entry.tags.clone(), entry.comment.clone()));
+
+            if update_comment(&comment_op, &mut entry.comment) {
+                cleared += 1;
+
+                if !args.no_touch {
+                    entry.update_ts();
+                }
+            }
+
+            if let Some(snapshot) = snapshot {
+                print_changes("update", &db_entry, snapshot, &entry.tags, entry.comment.as_ref());
+            }
+
+            continue;
+        }
+
+        let status = if context.db.files.contains_key(&db_entry) {
+            "update"
+        } else {
+            "new"
+        };
+
+        let entry = if args.no_touch {
+            context.db.files.entry(db_entry.clone())
+                .or_default()
+        } else {
+            context.db.files.entry(db_entry.clone())
+                .and_modify(db::FileData::update_ts)
+                .or_default()
+        };
+
+        let snapshot = args.dry_run
+            .then(|| (entry.tags.clone(), entry.comment.clone()));
 
         update_tags(&args, &mut entry.tags);
+        if update_comment(&comment_op, &mut entry.comment) {
+            cleared += 1;
+        }
+
+        entry.tags.extend(spec_tags);
 
-        if args.drop_comment {
-            entry.comment = None;
-        } else if let Some(comment) = &args.comment {
-            entry.comment = Some(comment.clone());
+        if let Some(created) = args.set_created {
+            entry.created = created;
         }
+
+        if let Some(updated) = args.set_updated {
+            entry.updated = Some(updated);
+        }
+
+        if let Some(snapshot) = snapshot {
+            print_changes(status, &db_entry, snapshot, &entry.tags, entry.comment.as_ref());
+        }
+    }
+
+    if skipped > 0 {
+        println!("skipped {} paths that could not be resolved", skipped);
+    }
+
+    if invalid > 0 {
+        println!("skipped {} lines that could not be parsed", invalid);
+    }
+
+    if args.dry_run {
+        log::info!("dry run, skipping save");
+
+        if args.clear_comment_if.is_some() {
+            println!("would clear {} comments", cleared);
+        }
+
+        return Ok(());
     }
 
     context.save()?;
 
+    if args.clear_comment_if.is_some() {
+        println!("cleared {} comments", cleared);
+    }
+
     Ok(())
 }
@@ -2,9 +2,47 @@ use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::str::FromStr;
 
+use clap::{Args, Subcommand};
 use serde::{Serialize, Deserialize};
 use url::Url;
 
+use crate::time;
+
+pub mod rename;
+pub mod list;
+pub mod hist;
+pub mod export;
+
+#[derive(Debug, Args)]
+pub struct TagsArgs {
+    #[command(subcommand)]
+    cmd: ManageCmd
+}
+
+#[derive(Debug, Subcommand)]
+enum ManageCmd {
+    /// lists all tags in use and the number of entries using them
+    List(list::ListArgs),
+
+    /// renames a tag across all entries in the db
+    Rename(rename::RenameArgs),
+
+    /// displays a histogram of the number values of a tag
+    Hist(hist::HistArgs),
+
+    /// writes the tags in use and their value types without any file data
+    Export(export::ExportArgs),
+}
+
+pub fn manage(args: TagsArgs) -> anyhow::Result<()> {
+    match args.cmd {
+        ManageCmd::List(list_args) => list::list_tags(list_args),
+        ManageCmd::Rename(rename_args) => rename::rename_tag(rename_args),
+        ManageCmd::Hist(hist_args) => hist::hist_tag(hist_args),
+        ManageCmd::Export(export_args) => export::export_tags(export_args),
+    }
+}
+
 pub type TagsMap = BTreeMap<String, Option<TagValue>>;
 
 #[derive(Debug, thiserror::Error)]
@@ -13,6 +51,43 @@ pub struct InvalidTagChars;
 
 pub const INVALID_CHARS: [char; 4] = ['\\', ':', ',', '!'];
 
+pub const DEFAULT_URL_SCHEMES: [&str; 4] = ["http", "https", "ftp", "mailto"];
+const URL_SCHEMES_ENV: &str = "FSM_URL_SCHEMES";
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidSize {
+    #[error("invalid size number \"{0}\"")]
+    Number(String),
+
+    #[error("unknown size suffix \"{0}\"")]
+    Suffix(String),
+
+    #[error("size is too large")]
+    Overflow,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidUrl {
+    #[error(transparent)]
+    Parse(#[from] url::ParseError),
+
+    #[error("the url scheme \"{0}\" is not allowed")]
+    Scheme(String),
+}
+
+/// checks if the scheme of the given url is allowed
+///
+/// the allowed schemes can be overridden with a comma separated list in the
+/// `FSM_URL_SCHEMES` environment variable
+pub fn url_scheme_allowed(url: &Url) -> bool {
+    if let Ok(list) = std::env::var(URL_SCHEMES_ENV) {
+        list.split(',')
+            .any(|scheme| scheme.trim().eq_ignore_ascii_case(url.scheme()))
+    } else {
+        DEFAULT_URL_SCHEMES.contains(&url.scheme())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TagKey(String);
 
@@ -45,25 +120,106 @@ impl FromStr for TagKey {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TagValue {
     Number(i64),
     Bool(bool),
     Url(url::Url),
     Simple(String),
+    Float(f64),
+    Date(time::DateTime),
+    List(Vec<TagValue>),
+    Size(u64),
+    Path(String),
 }
 
 impl TagValue {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            TagValue::Number(_) => "number",
+            TagValue::Bool(_) => "bool",
+            TagValue::Url(_) => "url",
+            TagValue::Simple(_) => "string",
+            TagValue::Float(_) => "float",
+            TagValue::Date(_) => "date",
+            TagValue::List(_) => "list",
+            TagValue::Size(_) => "size",
+            TagValue::Path(_) => "path",
+        }
+    }
+
+    /// checks if the value matches the given string
+    ///
+    /// list values will match if any of their items match
+    pub fn matches(&self, given: &str) -> bool {
+        match self {
+            TagValue::List(list) => list.iter().any(|v| v.matches(given)),
+            _ => self.to_string() == given,
+        }
+    }
+
     fn parse_num(value: &str) -> Result<Self, std::num::ParseIntError> {
         Ok(TagValue::Number(value.parse()?))
     }
 
+    fn parse_float(value: &str) -> Result<Self, std::num::ParseFloatError> {
+        Ok(TagValue::Float(value.parse()?))
+    }
+
+    fn parse_date(value: &str) -> Result<Self, time::InvalidDateTime> {
+        Ok(TagValue::Date(time::parse_datetime(value)?))
+    }
+
     fn parse_bool(value: &str) -> Result<Self, std::str::ParseBoolError> {
         Ok(TagValue::Bool(value.parse()?))
     }
 
-    fn parse_url(value: &str) -> Result<Self, url::ParseError> {
-        Ok(TagValue::Url(Url::parse(value)?))
+    fn parse_size(value: &str) -> Result<Self, InvalidSize> {
+        let value = value.trim();
+        let split = value.find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(value.len());
+        let (number, suffix) = value.split_at(split);
+
+        let multiplier: u64 = match suffix.trim() {
+            "" | "B" => 1,
+            "K" | "KB" => 1_000,
+            "M" | "MB" => 1_000_000,
+            "G" | "GB" => 1_000_000_000,
+            "T" | "TB" => 1_000_000_000_000,
+            "KiB" => 1 << 10,
+            "MiB" => 1 << 20,
+            "GiB" => 1 << 30,
+            "TiB" => 1 << 40,
+            unknown => return Err(InvalidSize::Suffix(unknown.to_owned())),
+        };
+
+        if let Ok(whole) = number.parse::<u64>() {
+            return whole.checked_mul(multiplier)
+                .map(TagValue::Size)
+                .ok_or(InvalidSize::Overflow);
+        }
+
+        let Some(fractional) = number.parse::<f64>().ok().filter(|v| v.is_finite()) else {
+            return Err(InvalidSize::Number(number.to_owned()));
+        };
+
+        let bytes = (fractional * multiplier as f64).round();
+
+        if bytes >= u64::MAX as f64 {
+            return Err(InvalidSize::Overflow);
+        }
+
+        Ok(TagValue::Size(bytes as u64))
+    }
+
+    fn parse_url(value: &str) -> Result<Self, InvalidUrl> {
+        let url = Url::parse(value)?;
+
+        if !url_scheme_allowed(&url) {
+            return Err(InvalidUrl::Scheme(url.scheme().to_owned()));
+        }
+
+        Ok(TagValue::Url(url))
     }
 }
 
@@ -74,18 +230,62 @@ impl Display for TagValue {
             TagValue::Bool(v) => write!(f, "{}", v),
             TagValue::Url(v) => write!(f, "{}", v),
             TagValue::Simple(v) => write!(f, "{}", v),
+            TagValue::Float(v) => write!(f, "{}", v),
+            TagValue::Date(v) => write!(f, "{}", v.with_timezone(&chrono::Local)),
+            TagValue::Size(v) => write_size(f, *v),
+            TagValue::Path(v) => write!(f, "{}", v),
+            TagValue::List(list) => {
+                for (index, v) in list.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}", v)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// writes a byte count using the largest binary unit that fits
+fn write_size(f: &mut Formatter<'_>, bytes: u64) -> std::fmt::Result {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return write!(f, "{}B", bytes);
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = UNITS[0];
+
+    for next in UNITS {
+        value /= 1024.0;
+        unit = next;
+
+        if value < 1024.0 {
+            break;
         }
     }
+
+    let formatted = format!("{:.2}", value);
+    let trimmed = formatted.trim_end_matches('0')
+        .trim_end_matches('.');
+
+    write!(f, "{}{}", trimmed, unit)
 }
 
 impl From<&str> for TagValue {
     fn from(value: &str) -> Self {
         if let Ok(i64_value) = value.parse() {
             TagValue::Number(i64_value)
+        } else if let Some(f64_value) = value.parse::<f64>().ok().filter(|v| v.is_finite()) {
+            TagValue::Float(f64_value)
         } else if let Ok(bool_) = value.parse() {
             TagValue::Bool(bool_)
-        } else if let Ok(url) = value.parse() {
-            TagValue::Url(url)
+        } else if let Ok(url) = TagValue::parse_url(value) {
+            url
         } else {
             TagValue::Simple(value.to_owned())
         }
@@ -94,10 +294,28 @@ impl From<&str> for TagValue {
 
 pub type Tag = (String, Option<TagValue>);
 
+/// appends a value to an existing tag
+///
+/// if the tag already has a different value then it will be converted into a
+/// list. values that are already present will not be added again
+pub fn append_value(existing: &mut Option<TagValue>, value: TagValue) {
+    match existing {
+        Some(TagValue::List(list)) => if !list.contains(&value) {
+            list.push(value);
+        }
+        Some(current) => if *current != value {
+            let prev = std::mem::replace(current, TagValue::List(Vec::new()));
+
+            *current = TagValue::List(vec![prev, value]);
+        }
+        None => *existing = Some(value),
+    }
+}
+
 pub fn parse_tag(arg: &str) -> Result<Tag, String> {
     if let Some((name, value)) = arg.split_once(':') {
         if name.is_empty() {
-            return Err(format!("tag name is empty"));
+            return Err("tag name is empty".to_owned());
         }
 
         if value.is_empty() {
@@ -107,29 +325,87 @@ pub fn parse_tag(arg: &str) -> Result<Tag, String> {
         }
     } else {
         if arg.is_empty() {
-            return Err(format!("tag is empty"));
+            return Err("tag is empty".to_owned());
         }
 
         Ok((arg.into(), None))
     }
 }
 
-fn get_name_value<'a>(arg: &'a str) -> Result<(&'a str, &'a str), String> {
+/// converts a scalar json value into a tag value
+fn json_scalar(key: &str, value: serde_json::Value) -> Result<TagValue, String> {
+    match value {
+        serde_json::Value::String(string) => Ok(TagValue::Simple(string)),
+        serde_json::Value::Bool(bool_) => Ok(TagValue::Bool(bool_)),
+        serde_json::Value::Number(number) => if let Some(i64_value) = number.as_i64() {
+            Ok(TagValue::Number(i64_value))
+        } else if let Some(f64_value) = number.as_f64().filter(|v| v.is_finite()) {
+            Ok(TagValue::Float(f64_value))
+        } else {
+            Err(format!("\"{}\" number is out of range", key))
+        }
+        _ => Err(format!("\"{}\" nested objects and arrays are not supported", key)),
+    }
+}
+
+/// parses a json object into a list of tags
+///
+/// strings are stored as string values, numbers as integers or floats, null
+/// as a tag without a value, and arrays as lists. nested objects and arrays
+/// are not supported
+pub fn parse_json_tags(given: &str) -> Result<Vec<Tag>, String> {
+    let parsed: serde_json::Value = serde_json::from_str(given)
+        .map_err(|err| format!("invalid json: {}", err))?;
+
+    let serde_json::Value::Object(map) = parsed else {
+        return Err("json must be an object".to_owned());
+    };
+
+    let mut rtn = Vec::with_capacity(map.len());
+
+    for (key, value) in map {
+        let value = match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::Array(list) => {
+                let mut items = Vec::with_capacity(list.len());
+
+                for item in list {
+                    items.push(json_scalar(&key, item)?);
+                }
+
+                Some(TagValue::List(items))
+            }
+            scalar => Some(json_scalar(&key, scalar)?),
+        };
+
+        rtn.push((key, value));
+    }
+
+    Ok(rtn)
+}
+
+fn get_name_value(arg: &str) -> Result<(&str, &str), String> {
     if let Some((name, value)) = arg.split_once(':') {
         if name.is_empty() {
-            return Err(format!("tag name is empty"));
+            return Err("tag name is empty".to_owned());
         }
 
         if value.is_empty() {
-            return Err(format!("missing url data"));
+            return Err("missing url data".to_owned());
         }
 
         Ok((name, value))
     } else {
-        Err(format!("missing tag value"))
+        Err("missing tag value".to_owned())
     }
 }
 
+pub fn parse_str_tag(arg: &str) -> Result<Tag, String> {
+    let (name, value) = get_name_value(arg)?;
+
+    Ok((name.into(), Some(TagValue::Simple(value.to_owned()))))
+}
+
 pub fn parse_url_tag(arg: &str) -> Result<Tag, String> {
     let (name, value) = get_name_value(arg)?;
 
@@ -148,6 +424,24 @@ pub fn parse_num_tag(arg: &str) -> Result<Tag, String> {
     }
 }
 
+pub fn parse_float_tag(arg: &str) -> Result<Tag, String> {
+    let (name, value) = get_name_value(arg)?;
+
+    match TagValue::parse_float(value) {
+        Ok(f) => Ok((name.into(), Some(f))),
+        Err(err) => Err(format!("invalid float provided: {}", err))
+    }
+}
+
+pub fn parse_date_tag(arg: &str) -> Result<Tag, String> {
+    let (name, value) = get_name_value(arg)?;
+
+    match TagValue::parse_date(value) {
+        Ok(d) => Ok((name.into(), Some(d))),
+        Err(err) => Err(format!("invalid date provided: {}", err))
+    }
+}
+
 pub fn parse_bool_tag(arg: &str) -> Result<Tag, String> {
     let (name, value) = get_name_value(arg)?;
 
@@ -156,3 +450,22 @@ pub fn parse_bool_tag(arg: &str) -> Result<Tag, String> {
         Err(err) => Err(format!("invalid bool provided: {}", err))
     }
 }
+
+pub fn parse_size_tag(arg: &str) -> Result<Tag, String> {
+    let (name, value) = get_name_value(arg)?;
+
+    match TagValue::parse_size(value) {
+        Ok(s) => Ok((name.into(), Some(s))),
+        Err(err) => Err(format!("invalid size provided: {}", err))
+    }
+}
+
+/// parses a path tag
+///
+/// the path is stored as given and is expected to be normalized to a db entry
+/// before being saved
+pub fn parse_path_tag(arg: &str) -> Result<Tag, String> {
+    let (name, value) = get_name_value(arg)?;
+
+    Ok((name.into(), Some(TagValue::Path(value.to_owned()))))
+}
@@ -0,0 +1,76 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::Args;
+use anyhow::Context;
+
+use crate::tags;
+use crate::db;
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// writes the tags to a file instead of stdout
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+type Vocabulary<'a> = BTreeMap<&'a str, BTreeSet<&'static str>>;
+
+fn collect_tags<'a>(map: &'a tags::TagsMap, vocabulary: &mut Vocabulary<'a>) {
+    for (key, value) in map {
+        let type_name = value.as_ref()
+            .map(tags::TagValue::type_name)
+            .unwrap_or("none");
+
+        vocabulary.entry(key.as_str())
+            .or_default()
+            .insert(type_name);
+    }
+}
+
+fn write_vocabulary<W>(mut output: W, vocabulary: &Vocabulary<'_>) -> anyhow::Result<()>
+where
+    W: Write
+{
+    serde_json::to_writer_pretty(&mut output, vocabulary)
+        .context("failed writing tags to output")?;
+
+    writeln!(output).context("failed writing tags to output")?;
+
+    output.flush().context("failed writing tags to output")?;
+
+    Ok(())
+}
+
+/// writes a json object of each tag key and the types of values it is used
+/// with
+pub fn export_tags(args: ExportArgs) -> anyhow::Result<()> {
+    let context = db::Context::load_readonly()?;
+
+    let mut vocabulary = Vocabulary::new();
+
+    collect_tags(&context.db.tags, &mut vocabulary);
+
+    for file in context.db.files.values() {
+        collect_tags(&file.tags, &mut vocabulary);
+    }
+
+    if let Some(out) = &args.out {
+        let file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(out)
+            .with_context(|| format!("failed to open output file: {}", out.display()))?;
+
+        write_vocabulary(BufWriter::new(file), &vocabulary)?;
+
+        println!("exported {} tags to {}", vocabulary.len(), out.display());
+    } else {
+        write_vocabulary(BufWriter::new(std::io::stdout().lock()), &vocabulary)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use clap::Args;
+
+use crate::tags;
+use crate::db;
+
+const BAR_WIDTH: usize = 40;
+
+#[derive(Debug, Args)]
+pub struct HistArgs {
+    /// the tag to display
+    key: String,
+
+    /// the number of buckets to split the values into
+    #[arg(long, default_value_t = 10, value_parser(clap::value_parser!(u64).range(1..)))]
+    buckets: u64,
+}
+
+/// collects the number values of a tag, counting values that are not numbers
+fn collect_values(value: &tags::TagValue, values: &mut Vec<f64>, ignored: &mut usize) {
+    match value {
+        tags::TagValue::Number(number) => values.push(*number as f64),
+        tags::TagValue::Float(float) => values.push(*float),
+        tags::TagValue::List(list) => for item in list {
+            collect_values(item, values, ignored);
+        }
+        _ => *ignored += 1,
+    }
+}
+
+/// formats a number with at most 2 decimal places
+fn format_number(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+
+    formatted.trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_owned()
+}
+
+pub fn hist_tag(args: HistArgs) -> anyhow::Result<()> {
+    let context = db::Context::load_readonly()?;
+
+    let mut values = Vec::new();
+    let mut ignored = 0usize;
+
+    for file in context.db.files.values() {
+        match file.tags.get(&args.key) {
+            Some(Some(value)) => collect_values(value, &mut values, &mut ignored),
+            Some(None) => ignored += 1,
+            None => {}
+        }
+    }
+
+    if values.is_empty() {
+        println!("no number values found for \"{}\"", args.key);
+    } else {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let buckets = if min == max { 1 } else { args.buckets as usize };
+        let width = (max - min) / buckets as f64;
+
+        let mut counts = vec![0usize; buckets];
+
+        for value in &values {
+            let index = if width == 0.0 {
+                0
+            } else {
+                (((value - min) / width) as usize).min(buckets - 1)
+            };
+
+            counts[index] += 1;
+        }
+
+        let largest = counts.iter().copied().max().unwrap_or(0);
+
+        println!("min: {}", format_number(min));
+        println!("max: {}", format_number(max));
+
+        for (index, count) in counts.iter().enumerate() {
+            let start = min + width * index as f64;
+            let end = min + width * (index + 1) as f64;
+            let bar = "#".repeat(count * BAR_WIDTH / largest);
+
+            let line = format!("{:>10} - {:<10} {:>6} {}", format_number(start), format_number(end), count, bar);
+
+            println!("{}", line.trim_end());
+        }
+    }
+
+    if ignored > 0 {
+        println!("ignored {} values that are not numbers", ignored);
+    }
+
+    println!("Total: {}", values.len());
+
+    Ok(())
+}
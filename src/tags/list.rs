@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+use clap::Args;
+
+use crate::tags;
+use crate::db;
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// sorts the list by the number of entries using a tag, descending
+    #[arg(long)]
+    by_count: bool,
+
+    /// displays a breakdown of the value types for each tag
+    #[arg(long)]
+    types: bool,
+}
+
+#[derive(Default)]
+struct KeyStats {
+    count: usize,
+    types: BTreeMap<&'static str, usize>,
+}
+
+fn count_tags<'a>(map: &'a tags::TagsMap, stats: &mut BTreeMap<&'a str, KeyStats>) {
+    for (key, value) in map {
+        let type_name = value.as_ref()
+            .map(tags::TagValue::type_name)
+            .unwrap_or("none");
+
+        let entry = stats.entry(key.as_str()).or_default();
+        entry.count += 1;
+
+        *entry.types.entry(type_name).or_default() += 1;
+    }
+}
+
+pub fn list_tags(args: ListArgs) -> anyhow::Result<()> {
+    let context = db::Context::load_readonly()?;
+
+    let mut stats = BTreeMap::new();
+
+    count_tags(&context.db.tags, &mut stats);
+
+    for file in context.db.files.values() {
+        count_tags(&file.tags, &mut stats);
+    }
+
+    let mut list: Vec<(&str, KeyStats)> = stats.into_iter().collect();
+
+    if args.by_count {
+        list.sort_by_key(|(_, key_stats)| std::cmp::Reverse(key_stats.count));
+    }
+
+    for (key, key_stats) in &list {
+        println!("{}: {}", key, key_stats.count);
+
+        if args.types {
+            for (type_name, count) in &key_stats.types {
+                println!("    {}: {}", type_name, count);
+            }
+        }
+    }
+
+    println!("Total: {}", list.len());
+
+    Ok(())
+}
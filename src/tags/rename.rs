@@ -0,0 +1,97 @@
+use clap::Args;
+
+use crate::tags::{self, TagKey};
+use crate::db::{self, MetaContainer};
+
+#[derive(Debug, Args)]
+pub struct RenameArgs {
+    /// the current name of the tag
+    old: String,
+
+    /// the new name of the tag
+    new: TagKey,
+
+    /// replaces the value of the new tag if it already exists on an entry
+    ///
+    /// by default entries that already contain the new tag will be skipped
+    #[arg(long)]
+    overwrite: bool,
+}
+
+enum Renamed {
+    Missing,
+    Skipped,
+    Updated,
+}
+
+fn rename_key(map: &mut tags::TagsMap, args: &RenameArgs) -> Renamed {
+    if !map.contains_key(&args.old) {
+        return Renamed::Missing;
+    }
+
+    if !args.overwrite && map.contains_key(args.new.inner()) {
+        return Renamed::Skipped;
+    }
+
+    let value = map.remove(&args.old).unwrap();
+
+    map.insert(args.new.inner().to_owned(), value);
+
+    Renamed::Updated
+}
+
+pub fn rename_tag(args: RenameArgs) -> anyhow::Result<()> {
+    let mut context = db::Context::cwd_load()?;
+
+    if args.old == args.new.inner() {
+        println!("the old and new tag names are the same");
+        return Ok(());
+    }
+
+    let mut updated = 0usize;
+    let mut skipped = 0usize;
+
+    match rename_key(&mut context.db.tags, &args) {
+        Renamed::Missing => {},
+        Renamed::Skipped => {
+            log::info!("skipping db, {} already exists", args.new);
+
+            skipped += 1;
+        }
+        Renamed::Updated => {
+            context.db.update_ts();
+
+            updated += 1;
+        }
+    }
+
+    for (key, file) in context.db.files.iter_mut() {
+        match rename_key(&mut file.tags, &args) {
+            Renamed::Missing => {},
+            Renamed::Skipped => {
+                log::info!("skipping {}, {} already exists", key, args.new);
+
+                skipped += 1;
+            }
+            Renamed::Updated => {
+                log::info!("renamed tag for {}", key);
+
+                file.update_ts();
+
+                updated += 1;
+            }
+        }
+    }
+
+    if updated > 0 {
+        context.save()?;
+    }
+
+    println!("renamed {updated} entries");
+
+    if skipped > 0 {
+        println!("skipped {skipped} entries where \"{}\" already exists", args.new);
+    }
+
+    Ok(())
+}
@@ -1,5 +1,99 @@
+use chrono::{NaiveDate, NaiveDateTime, TimeZone};
+
 pub type DateTime = chrono::DateTime<chrono::Utc>;
 
+#[derive(Debug, thiserror::Error)]
+#[error("the provided value is not a valid RFC3339 datetime or YYYY-MM-DD date")]
+pub struct InvalidDateTime;
+
+#[derive(Debug, thiserror::Error)]
+#[error("the provided value is not a valid relative time (e.g. 3h, 7d, 2w), RFC3339 datetime, or YYYY-MM-DD date")]
+pub struct InvalidDateFilter;
+
+#[derive(Debug, thiserror::Error)]
+#[error("the provided value is not a valid relative time (e.g. 3h, 7d, 2w)")]
+pub struct InvalidAge;
+
+#[derive(Debug, thiserror::Error)]
+#[error("the provided value is not a valid strftime format")]
+pub struct InvalidDateFormat;
+
 pub fn datetime_now() -> DateTime {
     chrono::Utc::now()
 }
+
+/// parses a relative duration like `30s`, `15m`, `3h`, `7d`, or `2w`
+pub fn parse_relative(value: &str) -> Option<chrono::Duration> {
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(split);
+
+    let amount: i64 = amount.parse().ok()?;
+
+    match unit {
+        "s" => chrono::Duration::try_seconds(amount),
+        "m" => chrono::Duration::try_minutes(amount),
+        "h" => chrono::Duration::try_hours(amount),
+        "d" => chrono::Duration::try_days(amount),
+        "w" => chrono::Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+/// parses a datetime for filtering
+///
+/// relative values like `7d` are subtracted from the current time, otherwise
+/// the value is parsed by [`parse_datetime`]
+pub fn parse_date_filter(value: &str) -> Result<DateTime, InvalidDateFilter> {
+    if let Some(duration) = parse_relative(value) {
+        return datetime_now()
+            .checked_sub_signed(duration)
+            .ok_or(InvalidDateFilter);
+    }
+
+    parse_datetime(value).map_err(|_| InvalidDateFilter)
+}
+
+/// parses a relative duration and subtracts it from the current time
+pub fn parse_age(value: &str) -> Result<DateTime, InvalidAge> {
+    let duration = parse_relative(value).ok_or(InvalidAge)?;
+
+    datetime_now()
+        .checked_sub_signed(duration)
+        .ok_or(InvalidAge)
+}
+
+/// validates a strftime format string for displaying datetimes
+pub fn parse_date_format(value: &str) -> Result<String, InvalidDateFormat> {
+    let invalid = chrono::format::StrftimeItems::new(value)
+        .any(|item| matches!(item, chrono::format::Item::Error));
+
+    if invalid {
+        Err(InvalidDateFormat)
+    } else {
+        Ok(value.to_owned())
+    }
+}
+
+/// parses a datetime from RFC3339 or a common `YYYY-MM-DD` form
+///
+/// values without an offset are assumed to be in local time
+pub fn parse_datetime(value: &str) -> Result<DateTime, InvalidDateTime> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&chrono::Utc));
+    }
+
+    let naive = if let Ok(naive) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
+        naive
+    } else if let Ok(naive) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+        naive
+    } else if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        date.and_hms_opt(0, 0, 0).ok_or(InvalidDateTime)?
+    } else {
+        return Err(InvalidDateTime);
+    };
+
+    chrono::Local.from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .ok_or(InvalidDateTime)
+}